@@ -1,3 +1,4 @@
+import {expect} from "chai";
 import {ethers} from "hardhat";
 import {Plonky2Verifier} from "../typechain-types";
 
@@ -92,10 +93,8 @@ describe("Verifier", function () {
             let fri_query_init_zs_partial_p = [];
             let fri_query_init_quotient_v = [];
             let fri_query_init_quotient_p = [];
-            let fri_query_step0_v = [];
-            let fri_query_step0_p = [];
-            let fri_query_step1_v = [];
-            let fri_query_step1_p = [];
+            let fri_query_step_v = [];
+            let fri_query_step_p = [];
             for (let i = 0; i < conf.num_fri_query_round; ++i) {
                 let fri_query_init_constants_sigmas_v_size = conf.num_fri_query_init_constants_sigmas_v * conf.field_size;
                 fri_query_init_constants_sigmas_v.push(deserialize_vec(buf.subarray(pos, pos + fri_query_init_constants_sigmas_v_size), conf.field_size));
@@ -137,25 +136,21 @@ describe("Verifier", function () {
                 fri_query_init_quotient_p.push(deserialize_vec(buf.subarray(pos, pos + fri_query_init_quotient_p_size), conf.hash_size));
                 pos += fri_query_init_quotient_p_size;
 
-                let fri_query_step0_v_size = conf.num_fri_query_step0_v * conf.ext_field_size;
-                fri_query_step0_v.push(deserialize_vec(buf.subarray(pos, pos + fri_query_step0_v_size), conf.ext_field_size));
-                pos += fri_query_step0_v_size;
-
-                console.assert(conf.num_fri_query_step0_p == buf.readUint8(pos));
-                pos++;
-                let fri_query_step0_p_size = conf.num_fri_query_step0_p * conf.hash_size;
-                fri_query_step0_p.push(deserialize_vec(buf.subarray(pos, pos + fri_query_step0_p_size), conf.hash_size));
-                pos += fri_query_step0_p_size;
-
-                let fri_query_step1_v_size = conf.num_fri_query_step1_v * conf.ext_field_size;
-                fri_query_step1_v.push(deserialize_vec(buf.subarray(pos, pos + fri_query_step1_v_size), conf.ext_field_size));
-                pos += fri_query_step1_v_size;
-
-                console.assert(conf.num_fri_query_step1_p == buf.readUint8(pos));
-                pos++;
-                let fri_query_step1_p_size = conf.num_fri_query_step1_p * conf.hash_size;
-                fri_query_step1_p.push(deserialize_vec(buf.subarray(pos, pos + fri_query_step1_p_size), conf.hash_size));
-                pos += fri_query_step1_p_size;
+                let round_step_v = [];
+                let round_step_p = [];
+                for (let j = 0; j < conf.num_fri_query_step_v.length; ++j) {
+                    let fri_query_step_v_size = conf.num_fri_query_step_v[j] * conf.ext_field_size;
+                    round_step_v.push(deserialize_vec(buf.subarray(pos, pos + fri_query_step_v_size), conf.ext_field_size));
+                    pos += fri_query_step_v_size;
+
+                    console.assert(conf.num_fri_query_step_p[j] == buf.readUint8(pos));
+                    pos++;
+                    let fri_query_step_p_size = conf.num_fri_query_step_p[j] * conf.hash_size;
+                    round_step_p.push(deserialize_vec(buf.subarray(pos, pos + fri_query_step_p_size), conf.hash_size));
+                    pos += fri_query_step_p_size;
+                }
+                fri_query_step_v.push(round_step_v);
+                fri_query_step_p.push(round_step_p);
             }
 
             let fri_final_poly_ext_v_size = conf.num_fri_final_poly_ext_v * conf.ext_field_size;
@@ -172,6 +167,25 @@ describe("Verifier", function () {
 
             await verifier.execute_verify(buf);
         });
+
+        it("Should reject a proof with a tampered Merkle path", async function () {
+            const Verifier = await ethers.getContractFactory("Plonky2Verifier");
+            const verifier = await Verifier.deploy();
+
+            const buf = Buffer.from(proof[0], 'base64');
+            // The first sibling of the constants and sigmas Merkle proof in the first FRI query round.
+            let pos = (conf.num_wires_cap + conf.num_plonk_zs_partial_products_cap + conf.num_quotient_polys_cap) * conf.hash_size;
+            pos += (conf.num_openings_constants + conf.num_openings_plonk_sigmas + conf.num_openings_wires
+                + conf.num_openings_plonk_zs + conf.num_openings_plonk_zs_next + conf.num_openings_partial_products
+                + conf.num_openings_quotient_polys) * conf.ext_field_size;
+            pos += conf.num_fri_commit_round * conf.fri_commit_merkle_cap_height * conf.hash_size;
+            pos += conf.num_fri_query_init_constants_sigmas_v * conf.field_size;
+            console.assert(conf.num_fri_query_init_constants_sigmas_p == buf.readUint8(pos));
+            pos++;
+            buf[pos] ^= 1;
+
+            expect(await verifier.verify(buf)).to.equal(false);
+        });
     });
 
     describe("TestGoldilocks", function () {
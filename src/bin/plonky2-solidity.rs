@@ -0,0 +1,281 @@
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand, ValueEnum};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::types::Field;
+use plonky2::gates::noop::NoopGate;
+use plonky2::iop::witness::{PartialWitness, Witness};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, CommonCircuitData, VerifierOnlyCircuitData};
+use plonky2::plonk::config::PoseidonGoldilocksConfig;
+use plonky2::plonk::proof::ProofWithPublicInputs;
+use plonky2_solidity_verifier::calldata::{encode_calldata, encode_hex_0x, VerifierFunction};
+use plonky2_solidity_verifier::circuit_data::{load_circuit_data, save_circuit_data};
+use plonky2_solidity_verifier::config::KeccakGoldilocksConfig2;
+use plonky2_solidity_verifier::gates::GateRegistry;
+use plonky2_solidity_verifier::inspect::inspect_proof_json;
+use plonky2_solidity_verifier::project::{write_contracts, VerifierProject};
+use plonky2_solidity_verifier::templates::SolidityTemplates;
+use plonky2_solidity_verifier::verifier::{
+    generate_proof_base64, generate_solidity_verifier_with_templates,
+    generate_verifier_config_from_common_data, VerifierConfig,
+};
+use plonky2_solidity_verifier::wrap::{wrap_for_evm, WrappedProof};
+
+type F = GoldilocksField;
+type C = PoseidonGoldilocksConfig;
+type KC2 = KeccakGoldilocksConfig2;
+
+/// Generates Solidity verifiers for plonky2 proofs and encodes their calldata.
+#[derive(Parser)]
+#[command(name = "plonky2-solidity", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generates the verifier contracts of circuit data saved by `wrap` or `save_circuit_data`.
+    GenVerifier {
+        /// Circuit data, as JSON if the extension is `.json` and in binary otherwise.
+        #[arg(long)]
+        circuit: PathBuf,
+        /// Directory of the Solidity package, laid out as `./contract`.
+        #[arg(long)]
+        out_dir: PathBuf,
+        /// Proof bytes of the circuit, written as test fixtures if set.
+        #[arg(long)]
+        proof: Option<PathBuf>,
+        /// Directory overriding some of the built-in templates.
+        #[arg(long)]
+        templates: Option<PathBuf>,
+    },
+    /// Wraps a `PoseidonGoldilocksConfig` proof for the EVM, then writes its verifier project,
+    /// proof bytes and circuit data.
+    Wrap {
+        /// Circuit data of the inner proof, saved by `save_circuit_data`.
+        #[arg(long, required_unless_present = "dummy")]
+        circuit: Option<PathBuf>,
+        /// Bytes of the inner proof, as written by `ProofWithPublicInputs::to_bytes`.
+        #[arg(long, required_unless_present = "dummy")]
+        proof: Option<PathBuf>,
+        /// Wraps the proof of a dummy circuit instead.
+        #[arg(long, conflicts_with_all = ["circuit", "proof"])]
+        dummy: bool,
+        /// Directory of the Solidity package, laid out as `./contract`.
+        #[arg(long)]
+        out_dir: PathBuf,
+        /// Number of `NoopGate`s of the dummy circuit.
+        #[arg(long, default_value_t = 4_000)]
+        num_gates: usize,
+        /// Number of public inputs of the dummy circuit.
+        #[arg(long, default_value_t = 0)]
+        num_public_inputs: usize,
+    },
+    /// Encodes proof bytes, e.g. the `proof.bin` written by `wrap`, for the verifier contract.
+    EncodeProof {
+        #[arg(long)]
+        proof: PathBuf,
+        #[arg(long, value_enum, default_value_t = Encoding::Base64)]
+        encoding: Encoding,
+        /// Verifier function called by the `abi` encoding.
+        #[arg(long, value_enum, default_value_t = Function::Verify)]
+        function: Function,
+        /// Output file, stdout if not set.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Prints the sections of proof bytes and their offsets as JSON.
+    Inspect {
+        #[arg(long)]
+        proof: PathBuf,
+        /// The `conf.json` written along with the verifier contracts.
+        #[arg(long)]
+        conf: PathBuf,
+        /// Output file, stdout if not set.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Encoding {
+    Base64,
+    /// `0x` prefixed hex.
+    Hex,
+    /// Calldata of the verifier function, `0x` prefixed hex.
+    Abi,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Function {
+    /// `verify(bytes)`, returning whether the proof is valid.
+    Verify,
+    /// `execute_verify(bytes)`, reverting if the proof is invalid.
+    ExecuteVerify,
+}
+
+impl From<Function> for VerifierFunction {
+    fn from(function: Function) -> Self {
+        match function {
+            Function::Verify => VerifierFunction::Verify,
+            Function::ExecuteVerify => VerifierFunction::ExecuteVerify,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    env_logger::init();
+    match Cli::parse().command {
+        Command::GenVerifier {
+            circuit,
+            out_dir,
+            proof,
+            templates,
+        } => gen_verifier(circuit, out_dir, proof, templates),
+        Command::Wrap {
+            circuit,
+            proof,
+            dummy,
+            out_dir,
+            num_gates,
+            num_public_inputs,
+        } => {
+            let (proof, verifier_only, common) = if dummy {
+                dummy_proof(num_gates, num_public_inputs)?
+            } else {
+                // Both are required without `--dummy`.
+                let (common, verifier_only) = load_circuit_data::<_, F, C, 2>(circuit.unwrap())?;
+                let proof =
+                    ProofWithPublicInputs::from_bytes(std::fs::read(proof.unwrap())?, &common)?;
+                (proof, verifier_only, common)
+            };
+            wrap(out_dir, proof, verifier_only, common)
+        }
+        Command::EncodeProof {
+            proof,
+            encoding,
+            function,
+            out,
+        } => {
+            let bytes = std::fs::read(&proof)?;
+            let encoded = match encoding {
+                Encoding::Base64 => base64::encode(&bytes),
+                Encoding::Hex => encode_hex_0x(&bytes),
+                Encoding::Abi => encode_hex_0x(&encode_calldata(function.into(), &bytes)),
+            };
+            write_output(out, &encoded)
+        }
+        Command::Inspect { proof, conf, out } => {
+            let conf: VerifierConfig = serde_json::from_str(&std::fs::read_to_string(conf)?)?;
+            write_output(out, &inspect_proof_json(&std::fs::read(proof)?, &conf)?)
+        }
+    }
+}
+
+fn write_output(out: Option<PathBuf>, output: &str) -> Result<()> {
+    match out {
+        Some(out) => std::fs::write(out, output)?,
+        None => println!("{}", output),
+    }
+    Ok(())
+}
+
+fn gen_verifier(
+    circuit: PathBuf,
+    out_dir: PathBuf,
+    proof: Option<PathBuf>,
+    templates: Option<PathBuf>,
+) -> Result<()> {
+    let (common, verifier_only) = load_circuit_data::<_, F, KC2, 2>(&circuit)?;
+    let conf = generate_verifier_config_from_common_data(&common)?;
+    let templates = match templates {
+        Some(dir) => SolidityTemplates::from_dir(dir)?,
+        None => SolidityTemplates::default(),
+    };
+    let (contract, gates_lib, proof_lib) = generate_solidity_verifier_with_templates(
+        &conf,
+        &common,
+        &verifier_only,
+        &GateRegistry::default(),
+        &templates,
+    )?;
+
+    match proof {
+        Some(proof) => {
+            let proof = ProofWithPublicInputs::from_bytes(std::fs::read(proof)?, &common)?;
+            let proof_base64 = generate_proof_base64(&proof, &conf)?;
+            let project = VerifierProject {
+                conf,
+                contract,
+                gates_lib,
+                proof_lib,
+                proof_base64,
+            };
+            project.write(&out_dir)?;
+        }
+        None => write_contracts(&out_dir, &contract, &gates_lib, &proof_lib)?,
+    }
+    println!("Wrote the verifier contracts to {}", out_dir.display());
+    Ok(())
+}
+
+/// A proof of `num_gates` `NoopGate`s, with public inputs `0..num_public_inputs`.
+fn dummy_proof(
+    num_gates: usize,
+    num_public_inputs: usize,
+) -> Result<(
+    ProofWithPublicInputs<F, C, 2>,
+    VerifierOnlyCircuitData<C, 2>,
+    CommonCircuitData<F, C, 2>,
+)> {
+    let mut builder = CircuitBuilder::<F, 2>::new(CircuitConfig::standard_recursion_config());
+    for _ in 0..num_gates {
+        builder.add_gate(NoopGate, vec![]);
+    }
+    let pi = builder.add_virtual_targets(num_public_inputs);
+    builder.register_public_inputs(&pi);
+    let data = builder.build::<C>();
+    let mut pw = PartialWitness::new();
+    for (i, &t) in pi.iter().enumerate() {
+        pw.set_target(t, F::from_canonical_usize(i));
+    }
+    let proof = data.prove(pw)?;
+    Ok((proof, data.verifier_only, data.common))
+}
+
+fn wrap(
+    out_dir: PathBuf,
+    proof: ProofWithPublicInputs<F, C, 2>,
+    verifier_only: VerifierOnlyCircuitData<C, 2>,
+    common: CommonCircuitData<F, C, 2>,
+) -> Result<()> {
+    let WrappedProof {
+        verifier_only,
+        common,
+        conf,
+        contract,
+        gates_lib,
+        proof_lib,
+        proof_bytes,
+        proof_base64,
+        ..
+    } = wrap_for_evm(proof, verifier_only, common)?;
+    let project = VerifierProject {
+        conf,
+        contract,
+        gates_lib,
+        proof_lib,
+        proof_base64,
+    };
+    project.write(&out_dir)?;
+    std::fs::write(out_dir.join("proof.bin"), proof_bytes)?;
+    save_circuit_data(out_dir.join("circuit.bin"), &common, &verifier_only)?;
+    println!("Wrote the verifier project to {}", out_dir.display());
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use anyhow::Result;
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::config::GenericConfig;
+use plonky2::plonk::proof::ProofWithPublicInputs;
+
+use crate::verifier::{encode_hex, generate_proof_bytes, VerifierConfig};
+
+/// The functions of the generated `Plonky2Verifier` taking the proof bytes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum VerifierFunction {
+    /// `verify(bytes)`, a view returning whether the proof is valid.
+    Verify,
+    /// `execute_verify(bytes)`, reverting if the proof is invalid.
+    ExecuteVerify,
+}
+
+impl VerifierFunction {
+    pub fn signature(&self) -> &'static str {
+        match self {
+            VerifierFunction::Verify => "verify(bytes)",
+            VerifierFunction::ExecuteVerify => "execute_verify(bytes)",
+        }
+    }
+
+    pub fn selector(&self) -> [u8; 4] {
+        keccak_hash::keccak(self.signature()).0[..4]
+            .try_into()
+            .unwrap()
+    }
+}
+
+/// ABI encoding of a call to `function`: the selector, the offset and length of the `bytes`
+/// argument, then the proof right-padded with zeros to a multiple of 32 bytes.
+pub fn encode_calldata(function: VerifierFunction, proof: &[u8]) -> Vec<u8> {
+    let mut calldata = function.selector().to_vec();
+    let mut word = [0u8; 32];
+    word[31] = 0x20;
+    calldata.extend_from_slice(&word);
+    word[24..].copy_from_slice(&(proof.len() as u64).to_be_bytes());
+    calldata.extend_from_slice(&word);
+    calldata.extend_from_slice(proof);
+    calldata.resize(calldata.len() + (32 - proof.len() % 32) % 32, 0);
+    calldata
+}
+
+/// `0x` prefixed hex encoding, as taken by JSON-RPC `eth_call` and `eth_sendTransaction`.
+pub fn encode_hex_0x(bytes: &[u8]) -> String {
+    "0x".to_owned() + &encode_hex(bytes)
+}
+
+/// The calldata of `function` verifying `pwpi`, checked against the layout of `conf`.
+pub fn generate_proof_calldata<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    pwpi: &ProofWithPublicInputs<F, C, D>,
+    conf: &VerifierConfig,
+    function: VerifierFunction,
+) -> Result<Vec<u8>> {
+    Ok(encode_calldata(
+        function,
+        &generate_proof_bytes(pwpi, conf)?,
+    ))
+}
+
+/// Same as `generate_proof_calldata`, `0x` prefixed hex encoded.
+pub fn generate_proof_calldata_hex<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    pwpi: &ProofWithPublicInputs<F, C, D>,
+    conf: &VerifierConfig,
+    function: VerifierFunction,
+) -> Result<String> {
+    Ok(encode_hex_0x(&generate_proof_calldata(
+        pwpi, conf, function,
+    )?))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::plonk::config::GenericConfig;
+
+    use crate::calldata::{
+        encode_calldata, generate_proof_calldata, generate_proof_calldata_hex, VerifierFunction,
+    };
+    use crate::config::KeccakGoldilocksConfig2;
+    use crate::test_utils::dummy_proof;
+    use crate::verifier::{generate_verifier_config, proof_size};
+    use crate::wrap::final_config;
+
+    #[test]
+    fn test_encode_calldata() {
+        assert_eq!(
+            VerifierFunction::Verify.selector(),
+            [0x8e, 0x76, 0x0a, 0xfe]
+        );
+        assert_eq!(
+            VerifierFunction::ExecuteVerify.selector(),
+            [0x54, 0xdf, 0x2b, 0xb3]
+        );
+
+        let proof = [0xabu8; 33];
+        let calldata = encode_calldata(VerifierFunction::ExecuteVerify, &proof);
+        assert_eq!(calldata.len(), 4 + 32 + 32 + 64);
+        assert_eq!(calldata[..4], [0x54, 0xdf, 0x2b, 0xb3]);
+        assert_eq!(calldata[35], 0x20);
+        assert_eq!(calldata[67], 33);
+        assert_eq!(calldata[68..101], proof);
+        assert!(calldata[101..].iter().all(|&b| b == 0));
+        assert!(calldata[4..35]
+            .iter()
+            .chain(&calldata[36..67])
+            .all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_generate_proof_calldata() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+
+        let (proof, _, _) = dummy_proof::<F, KC2, D>(&final_config(), 4_000, 1)?;
+        let conf = generate_verifier_config(&proof)?;
+
+        let calldata = generate_proof_calldata(&proof, &conf, VerifierFunction::Verify)?;
+        let size = proof_size(&conf);
+        assert_eq!(calldata.len(), 4 + 64 + (size + 31) / 32 * 32);
+        assert_eq!(calldata[68..68 + size], proof.to_bytes()?);
+
+        let hex = generate_proof_calldata_hex(&proof, &conf, VerifierFunction::Verify)?;
+        assert!(hex.starts_with("0x8e760afe"));
+        assert_eq!(hex.len(), 2 + 2 * calldata.len());
+        Ok(())
+    }
+}
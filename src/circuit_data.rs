@@ -0,0 +1,653 @@
+use std::ops::Range;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::{Field, PrimeField64};
+use plonky2::fri::reduction_strategies::FriReductionStrategy;
+use plonky2::fri::{FriConfig, FriParams};
+use plonky2::gates::arithmetic_base::ArithmeticGate;
+use plonky2::gates::arithmetic_extension::ArithmeticExtensionGate;
+use plonky2::gates::base_sum::BaseSumGate;
+use plonky2::gates::constant::ConstantGate;
+use plonky2::gates::exponentiation::ExponentiationGate;
+use plonky2::gates::gate::GateRef;
+use plonky2::gates::low_degree_interpolation::LowDegreeInterpolationGate;
+use plonky2::gates::multiplication_extension::MulExtensionGate;
+use plonky2::gates::noop::NoopGate;
+use plonky2::gates::poseidon::PoseidonGate;
+use plonky2::gates::poseidon_mds::PoseidonMdsGate;
+use plonky2::gates::public_input::PublicInputGate;
+use plonky2::gates::random_access::RandomAccessGate;
+use plonky2::gates::reducing::ReducingGate;
+use plonky2::gates::reducing_extension::ReducingExtensionGate;
+use plonky2::gates::selectors::SelectorsInfo;
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::merkle_tree::MerkleCap;
+use plonky2::plonk::circuit_data::{CircuitConfig, CommonCircuitData, VerifierOnlyCircuitData};
+use plonky2::plonk::config::{GenericConfig, GenericHashOut};
+use serde::{Deserialize, Serialize};
+
+use crate::error::GeneratorError;
+use crate::gates::{gate_type, GateRegistry};
+
+/// Version of the `SerializedCircuitData` format, bumped on incompatible changes.
+pub const CIRCUIT_DATA_VERSION: u32 = 1;
+
+/// A gate of the circuit, with the parameters needed to rebuild it.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub enum SerializedGate {
+    Noop,
+    PublicInput,
+    Constant {
+        num_consts: usize,
+    },
+    Arithmetic {
+        num_ops: usize,
+    },
+    ArithmeticExtension {
+        num_ops: usize,
+    },
+    MulExtension {
+        num_ops: usize,
+    },
+    /// `BaseSumGate<2>`, the only base used by plonky2.
+    BaseSum {
+        num_limbs: usize,
+    },
+    Reducing {
+        num_coeffs: usize,
+    },
+    ReducingExtension {
+        num_coeffs: usize,
+    },
+    /// `num_copies` and `num_extra_constants` follow from `bits` and the circuit config, they are
+    /// stored to check that the rebuilt gate matches.
+    RandomAccess {
+        bits: usize,
+        num_copies: usize,
+        num_extra_constants: usize,
+    },
+    Exponentiation {
+        num_power_bits: usize,
+    },
+    LowDegreeInterpolation {
+        subgroup_bits: usize,
+    },
+    Poseidon,
+    PoseidonMds,
+    /// A gate serialized by the `SolidityGate` registered for its type, see
+    /// `SolidityGate::serialize_gate`.
+    Custom {
+        gate_type: String,
+        params: Vec<u8>,
+    },
+}
+
+impl SerializedGate {
+    /// Serializes `gate` with the `SolidityGate` registered for its type if it supports it, and as
+    /// one of the built-in gates otherwise.
+    pub fn new<F: RichField + Extendable<D>, const D: usize>(
+        gate: &GateRef<F, D>,
+        config: &CircuitConfig,
+        gates: &GateRegistry<F, D>,
+    ) -> Result<Self, GeneratorError> {
+        let id = gate.0.id();
+        if let Some(params) = gates.get(gate).and_then(|g| g.serialize_gate(gate)) {
+            return Ok(Self::Custom {
+                gate_type: gate_type(&id).to_owned(),
+                params,
+            });
+        }
+        Self::from_id(&id)
+            // The parameters are read from the gate id, check that none was missed.
+            .filter(|serialized| {
+                serialized
+                    .to_gate::<F, D>(config, gates)
+                    .map_or(false, |g| g.0.id() == id)
+            })
+            .ok_or(GeneratorError::UnsupportedGate(id))
+    }
+
+    /// Reads the parameters of a built-in gate from its id, e.g. `ArithmeticGate { num_ops: 20 }`.
+    fn from_id(id: &str) -> Option<Self> {
+        let param = |name| gate_param(id, name);
+        Some(match gate_type(id) {
+            "NoopGate" => Self::Noop,
+            "PublicInputGate" => Self::PublicInput,
+            "ConstantGate" => Self::Constant {
+                num_consts: param("num_consts")?,
+            },
+            "ArithmeticGate" => Self::Arithmetic {
+                num_ops: param("num_ops")?,
+            },
+            "ArithmeticExtensionGate" => Self::ArithmeticExtension {
+                num_ops: param("num_ops")?,
+            },
+            "MulExtensionGate" => Self::MulExtension {
+                num_ops: param("num_ops")?,
+            },
+            "BaseSumGate" if param("Base") == Some(2) => Self::BaseSum {
+                num_limbs: param("num_limbs")?,
+            },
+            "ReducingGate" => Self::Reducing {
+                num_coeffs: param("num_coeffs")?,
+            },
+            "ReducingExtensionGate" => Self::ReducingExtension {
+                num_coeffs: param("num_coeffs")?,
+            },
+            "RandomAccessGate" => Self::RandomAccess {
+                bits: param("bits")?,
+                num_copies: param("num_copies")?,
+                num_extra_constants: param("num_extra_constants")?,
+            },
+            "ExponentiationGate" => Self::Exponentiation {
+                num_power_bits: param("num_power_bits")?,
+            },
+            "LowDegreeInterpolationGate" => Self::LowDegreeInterpolation {
+                subgroup_bits: param("subgroup_bits")?,
+            },
+            "PoseidonGate" => Self::Poseidon,
+            "PoseidonMdsGate" => Self::PoseidonMds,
+            _ => return None,
+        })
+    }
+
+    /// Rebuilds the gate, `None` if a custom gate has no registered `SolidityGate` rebuilding it.
+    pub fn to_gate<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        config: &CircuitConfig,
+        gates: &GateRegistry<F, D>,
+    ) -> Option<GateRef<F, D>> {
+        Some(match *self {
+            Self::Noop => GateRef::new(NoopGate),
+            Self::PublicInput => GateRef::new(PublicInputGate),
+            Self::Constant { num_consts } => GateRef::new(ConstantGate::new(num_consts)),
+            Self::Arithmetic { num_ops } => GateRef::new(ArithmeticGate { num_ops }),
+            Self::ArithmeticExtension { num_ops } => {
+                GateRef::new(ArithmeticExtensionGate::<D> { num_ops })
+            }
+            Self::MulExtension { num_ops } => GateRef::new(MulExtensionGate::<D> { num_ops }),
+            Self::BaseSum { num_limbs } => GateRef::new(BaseSumGate::<2>::new(num_limbs)),
+            Self::Reducing { num_coeffs } => GateRef::new(ReducingGate::<D>::new(num_coeffs)),
+            Self::ReducingExtension { num_coeffs } => {
+                GateRef::new(ReducingExtensionGate::<D>::new(num_coeffs))
+            }
+            Self::RandomAccess {
+                bits,
+                num_copies,
+                num_extra_constants,
+            } => {
+                let gate = RandomAccessGate::<F, D>::new_from_config(config, bits);
+                if gate.num_copies != num_copies || gate.num_extra_constants != num_extra_constants
+                {
+                    return None;
+                }
+                GateRef::new(gate)
+            }
+            Self::Exponentiation { num_power_bits } => {
+                GateRef::new(ExponentiationGate::<F, D>::new(num_power_bits))
+            }
+            Self::LowDegreeInterpolation { subgroup_bits } => {
+                GateRef::new(LowDegreeInterpolationGate::<F, D>::new(subgroup_bits))
+            }
+            Self::Poseidon => GateRef::new(PoseidonGate::<F, D>::new()),
+            Self::PoseidonMds => GateRef::new(PoseidonMdsGate::<F, D>::new()),
+            Self::Custom {
+                ref gate_type,
+                ref params,
+            } => {
+                let gate = gates
+                    .get_type(gate_type)?
+                    .deserialize_gate(params, config)?;
+                if crate::gates::gate_type(&gate.0.id()) != gate_type {
+                    return None;
+                }
+                gate
+            }
+        })
+    }
+
+    /// The gate type, as in its id.
+    fn gate_type(&self) -> &str {
+        match self {
+            Self::Noop => "NoopGate",
+            Self::PublicInput => "PublicInputGate",
+            Self::Constant { .. } => "ConstantGate",
+            Self::Arithmetic { .. } => "ArithmeticGate",
+            Self::ArithmeticExtension { .. } => "ArithmeticExtensionGate",
+            Self::MulExtension { .. } => "MulExtensionGate",
+            Self::BaseSum { .. } => "BaseSumGate",
+            Self::Reducing { .. } => "ReducingGate",
+            Self::ReducingExtension { .. } => "ReducingExtensionGate",
+            Self::RandomAccess { .. } => "RandomAccessGate",
+            Self::Exponentiation { .. } => "ExponentiationGate",
+            Self::LowDegreeInterpolation { .. } => "LowDegreeInterpolationGate",
+            Self::Poseidon => "PoseidonGate",
+            Self::PoseidonMds => "PoseidonMdsGate",
+            Self::Custom { gate_type, .. } => gate_type,
+        }
+    }
+}
+
+/// The parts of `CommonCircuitData` and `VerifierOnlyCircuitData` the verifier generator uses, so
+/// that contracts can be regenerated without rebuilding the circuit.
+///
+/// Gates are stored with their parameters, see `SerializedGate`. The FRI reduction strategy is not
+/// stored, the loaded config uses the resulting arities instead.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct SerializedCircuitData {
+    pub version: u32,
+
+    // circuit config
+    pub num_wires: usize,
+    pub num_routed_wires: usize,
+    pub num_constants: usize,
+    pub use_base_arithmetic_gate: bool,
+    pub security_bits: usize,
+    pub num_challenges: usize,
+    pub zero_knowledge: bool,
+    pub max_quotient_degree_factor: usize,
+    // fri params
+    pub rate_bits: usize,
+    pub cap_height: usize,
+    pub proof_of_work_bits: u32,
+    pub num_query_rounds: usize,
+    pub hiding: bool,
+    pub degree_bits: usize,
+    pub reduction_arity_bits: Vec<usize>,
+
+    pub gates: Vec<SerializedGate>,
+    pub selector_indices: Vec<usize>,
+    pub selector_groups: Vec<Range<usize>>,
+    pub quotient_degree_factor: usize,
+    pub num_gate_constraints: usize,
+    /// `CommonCircuitData::num_constants`, i.e. the selectors and gate constants.
+    pub num_constant_polys: usize,
+    pub num_public_inputs: usize,
+    pub k_is: Vec<u64>,
+    pub num_partial_products: usize,
+    pub circuit_digest: Vec<u8>,
+
+    // verifier only
+    pub constants_sigmas_cap: Vec<Vec<u8>>,
+}
+
+impl SerializedCircuitData {
+    /// Fails if a gate is neither a built-in gate nor serialized by its `SolidityGate`.
+    pub fn new<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+        common: &CommonCircuitData<F, C, D>,
+        verifier_only: &VerifierOnlyCircuitData<C, D>,
+        gates: &GateRegistry<F, D>,
+    ) -> Result<Self, GeneratorError> {
+        let config = &common.config;
+        Ok(Self {
+            version: CIRCUIT_DATA_VERSION,
+            num_wires: config.num_wires,
+            num_routed_wires: config.num_routed_wires,
+            num_constants: config.num_constants,
+            use_base_arithmetic_gate: config.use_base_arithmetic_gate,
+            security_bits: config.security_bits,
+            num_challenges: config.num_challenges,
+            zero_knowledge: config.zero_knowledge,
+            max_quotient_degree_factor: config.max_quotient_degree_factor,
+            rate_bits: config.fri_config.rate_bits,
+            cap_height: config.fri_config.cap_height,
+            proof_of_work_bits: config.fri_config.proof_of_work_bits,
+            num_query_rounds: config.fri_config.num_query_rounds,
+            hiding: common.fri_params.hiding,
+            degree_bits: common.degree_bits,
+            reduction_arity_bits: common.fri_params.reduction_arity_bits.clone(),
+            gates: common
+                .gates
+                .iter()
+                .map(|gate| SerializedGate::new(gate, config, gates))
+                .collect::<Result<_, _>>()?,
+            selector_indices: common.selectors_info.selector_indices.clone(),
+            selector_groups: common.selectors_info.groups.clone(),
+            quotient_degree_factor: common.quotient_degree_factor,
+            num_gate_constraints: common.num_gate_constraints,
+            num_constant_polys: common.num_constants,
+            num_public_inputs: common.num_public_inputs,
+            k_is: common.k_is.iter().map(|k| k.to_canonical_u64()).collect(),
+            num_partial_products: common.num_partial_products,
+            circuit_digest: common.circuit_digest.to_bytes(),
+            constants_sigmas_cap: verifier_only
+                .constants_sigmas_cap
+                .0
+                .iter()
+                .map(|h| h.to_bytes())
+                .collect(),
+        })
+    }
+
+    /// Rebuilds the circuit data, failing if a gate cannot be rebuilt.
+    pub fn to_circuit_data<
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        const D: usize,
+    >(
+        &self,
+        gates: &GateRegistry<F, D>,
+    ) -> Result<(CommonCircuitData<F, C, D>, VerifierOnlyCircuitData<C, D>)> {
+        if self.version != CIRCUIT_DATA_VERSION {
+            bail!(
+                "unsupported circuit data version {}, expected {}",
+                self.version,
+                CIRCUIT_DATA_VERSION
+            );
+        }
+
+        let fri_config = FriConfig {
+            rate_bits: self.rate_bits,
+            cap_height: self.cap_height,
+            proof_of_work_bits: self.proof_of_work_bits,
+            reduction_strategy: FriReductionStrategy::Fixed(self.reduction_arity_bits.clone()),
+            num_query_rounds: self.num_query_rounds,
+        };
+        let config = CircuitConfig {
+            num_wires: self.num_wires,
+            num_routed_wires: self.num_routed_wires,
+            num_constants: self.num_constants,
+            use_base_arithmetic_gate: self.use_base_arithmetic_gate,
+            security_bits: self.security_bits,
+            num_challenges: self.num_challenges,
+            zero_knowledge: self.zero_knowledge,
+            max_quotient_degree_factor: self.max_quotient_degree_factor,
+            fri_config: fri_config.clone(),
+        };
+        let gates = self
+            .gates
+            .iter()
+            .map(|gate| {
+                gate.to_gate(&config, gates)
+                    .ok_or_else(|| GeneratorError::UnsupportedGate(gate.gate_type().to_owned()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let common = CommonCircuitData {
+            fri_params: FriParams {
+                config: fri_config,
+                hiding: self.hiding,
+                degree_bits: self.degree_bits,
+                reduction_arity_bits: self.reduction_arity_bits.clone(),
+            },
+            config,
+            degree_bits: self.degree_bits,
+            gates,
+            selectors_info: SelectorsInfo {
+                selector_indices: self.selector_indices.clone(),
+                groups: self.selector_groups.clone(),
+            },
+            quotient_degree_factor: self.quotient_degree_factor,
+            num_gate_constraints: self.num_gate_constraints,
+            num_constants: self.num_constant_polys,
+            num_public_inputs: self.num_public_inputs,
+            k_is: self
+                .k_is
+                .iter()
+                .map(|&k| F::from_canonical_u64(k))
+                .collect(),
+            num_partial_products: self.num_partial_products,
+            circuit_digest: GenericHashOut::from_bytes(&self.circuit_digest),
+        };
+        let verifier_only = VerifierOnlyCircuitData {
+            constants_sigmas_cap: MerkleCap(
+                self.constants_sigmas_cap
+                    .iter()
+                    .map(|h| GenericHashOut::from_bytes(h))
+                    .collect(),
+            ),
+        };
+        Ok((common, verifier_only))
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// Saves the circuit data to `path`, as JSON if its extension is `.json` and in binary otherwise.
+pub fn save_circuit_data<
+    P: AsRef<Path>,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    path: P,
+    common: &CommonCircuitData<F, C, D>,
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+) -> Result<()> {
+    save_circuit_data_with_gates(path, common, verifier_only, &GateRegistry::default())
+}
+
+/// Same as `save_circuit_data`, serializing custom gates with the `SolidityGate`s of `gates`.
+pub fn save_circuit_data_with_gates<
+    P: AsRef<Path>,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    path: P,
+    common: &CommonCircuitData<F, C, D>,
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+    gates: &GateRegistry<F, D>,
+) -> Result<()> {
+    let data = SerializedCircuitData::new(common, verifier_only, gates)?;
+    let path = path.as_ref();
+    if is_json(path) {
+        std::fs::write(path, data.to_json()?)?;
+    } else {
+        std::fs::write(path, data.to_bytes()?)?;
+    }
+    Ok(())
+}
+
+/// Loads circuit data saved by `save_circuit_data`.
+pub fn load_circuit_data<
+    P: AsRef<Path>,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    path: P,
+) -> Result<(CommonCircuitData<F, C, D>, VerifierOnlyCircuitData<C, D>)> {
+    load_circuit_data_with_gates(path, &GateRegistry::default())
+}
+
+/// Same as `load_circuit_data`, rebuilding custom gates with the `SolidityGate`s of `gates`.
+pub fn load_circuit_data_with_gates<
+    P: AsRef<Path>,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    path: P,
+    gates: &GateRegistry<F, D>,
+) -> Result<(CommonCircuitData<F, C, D>, VerifierOnlyCircuitData<C, D>)> {
+    let path = path.as_ref();
+    let data = if is_json(path) {
+        SerializedCircuitData::from_json(&std::fs::read_to_string(path)?)?
+    } else {
+        SerializedCircuitData::from_bytes(&std::fs::read(path)?)?
+    };
+    data.to_circuit_data(gates)
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == "json")
+}
+
+/// The value of the parameter `name` in a gate id, e.g. 20 for `num_ops` in
+/// `ArithmeticGate { num_ops: 20 }`.
+fn gate_param(id: &str, name: &str) -> Option<usize> {
+    let pattern = name.to_owned() + ": ";
+    let (i, _) = id
+        .match_indices(&pattern)
+        .find(|(i, _)| !id[..*i].ends_with(|c: char| c.is_alphanumeric() || c == '_'))?;
+    let value = &id[i + pattern.len()..];
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::types::Field;
+    use plonky2::gates::gate::GateRef;
+    use plonky2::gates::noop::NoopGate;
+    use plonky2::hash::poseidon::PoseidonHash;
+    use plonky2::iop::witness::{PartialWitness, Witness};
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::{CircuitConfig, CommonCircuitData, VerifierOnlyCircuitData};
+    use plonky2::plonk::config::GenericConfig;
+    use plonky2::plonk::proof::ProofWithPublicInputs;
+
+    use crate::circuit_data::{gate_param, SerializedCircuitData, SerializedGate};
+    use crate::config::KeccakGoldilocksConfig2;
+    use crate::gates::{ExportedSolidityGate, GateRegistry, SolidityGate};
+    use crate::test_utils::dummy_circuit;
+    use crate::verifier::{
+        generate_solidity_verifier, generate_verifier_config,
+        generate_verifier_config_from_common_data,
+    };
+    use crate::wrap::final_config;
+
+    #[test]
+    fn test_serialized_circuit_data() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+
+        let mut builder = CircuitBuilder::<F, D>::new(final_config());
+        let inputs = builder.add_virtual_targets(4);
+        let hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(inputs.clone());
+        let product = builder.mul(inputs[0], inputs[1]);
+        builder.register_public_inputs(&hash.elements);
+        builder.register_public_input(product);
+        let data = builder.build::<KC2>();
+        let mut pw = PartialWitness::new();
+        for (i, &t) in inputs.iter().enumerate() {
+            pw.set_target(t, F::from_canonical_usize(i + 1));
+        }
+        let proof = data.prove(pw)?;
+
+        let gates = GateRegistry::default();
+        let serialized = SerializedCircuitData::new(&data.common, &data.verifier_only, &gates)?;
+        assert!(serialized.gates.contains(&SerializedGate::Poseidon));
+        let bytes = serialized.to_bytes()?;
+        assert_eq!(SerializedCircuitData::from_bytes(&bytes)?, serialized);
+        let json = serialized.to_json()?;
+        assert_eq!(SerializedCircuitData::from_json(&json)?, serialized);
+
+        let (common, verifier_only): (
+            CommonCircuitData<F, KC2, D>,
+            VerifierOnlyCircuitData<KC2, D>,
+        ) = serialized.to_circuit_data(&gates)?;
+        assert_eq!(
+            SerializedCircuitData::new(&common, &verifier_only, &gates)?,
+            serialized
+        );
+
+        // The proof can be read and the same contracts generated with the loaded data.
+        let loaded_proof = ProofWithPublicInputs::from_bytes(proof.to_bytes()?, &common)?;
+        let conf = generate_verifier_config(&loaded_proof)?;
+        assert_eq!(conf, generate_verifier_config_from_common_data(&common)?);
+        assert_eq!(
+            generate_solidity_verifier(&conf, &common, &verifier_only)?,
+            generate_solidity_verifier(&conf, &data.common, &data.verifier_only)?
+        );
+
+        let mut unknown_gate = serialized.clone();
+        unknown_gate.gates.push(SerializedGate::Custom {
+            gate_type: "CustomGate".to_owned(),
+            params: vec![3],
+        });
+        assert!(unknown_gate
+            .to_circuit_data::<F, KC2, D>(&gates)
+            .unwrap_err()
+            .to_string()
+            .contains("CustomGate"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_registered_gate() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+
+        /// Saves `NoopGate`s as custom gates.
+        struct CustomNoopGate;
+        impl SolidityGate<F, D> for CustomNoopGate {
+            fn export_solidity_verification_code(&self, gate: &GateRef<F, D>) -> String {
+                ExportedSolidityGate.export_solidity_verification_code(gate)
+            }
+
+            fn serialize_gate(&self, _: &GateRef<F, D>) -> Option<Vec<u8>> {
+                Some(vec![])
+            }
+
+            fn deserialize_gate(&self, params: &[u8], _: &CircuitConfig) -> Option<GateRef<F, D>> {
+                params.is_empty().then(|| GateRef::new(NoopGate))
+            }
+        }
+
+        let data = dummy_circuit::<F, KC2, D>(&final_config(), 100, 0);
+        let mut gates = GateRegistry::default();
+        gates.register("NoopGate", CustomNoopGate);
+        let serialized = SerializedCircuitData::new(&data.common, &data.verifier_only, &gates)?;
+        assert!(serialized.gates.contains(&SerializedGate::Custom {
+            gate_type: "NoopGate".to_owned(),
+            params: vec![],
+        }));
+
+        let (common, _): (
+            CommonCircuitData<F, KC2, D>,
+            VerifierOnlyCircuitData<KC2, D>,
+        ) = SerializedCircuitData::from_json(&serialized.to_json()?)?.to_circuit_data(&gates)?;
+        assert_eq!(
+            common.gates.iter().map(|g| g.0.id()).collect::<Vec<_>>(),
+            data.common
+                .gates
+                .iter()
+                .map(|g| g.0.id())
+                .collect::<Vec<_>>()
+        );
+        // Without the registered gate, the custom gate cannot be rebuilt.
+        assert!(serialized
+            .to_circuit_data::<F, KC2, D>(&GateRegistry::default())
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_gate_param() {
+        let id = "RandomAccessGate { bits: 4, num_copies: 4, num_extra_constants: 2, _phantom: PhantomData<u64> }<D=2>";
+        assert_eq!(gate_param(id, "bits"), Some(4));
+        assert_eq!(gate_param(id, "num_extra_constants"), Some(2));
+        assert_eq!(
+            gate_param("BaseSumGate { num_limbs: 63 } + Base: 2", "Base"),
+            Some(2)
+        );
+        assert_eq!(
+            gate_param("ExponentiationGate { num_power_bits: 66 }", "bits"),
+            None
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt;
+
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::plonk::config::{GenericConfig, Hasher};
+
+use crate::error::GeneratorError;
+use crate::gates::GateRegistry;
+
+/// Every issue preventing a circuit from being verified by a generated Solidity verifier,
+/// along with the FRI shape the verifier would be generated for.
+#[derive(Debug)]
+pub struct CompatibilityReport {
+    pub issues: Vec<GeneratorError>,
+
+    pub reduction_arity_bits: Vec<usize>,
+    pub cap_height: usize,
+    pub zero_knowledge: bool,
+}
+
+impl CompatibilityReport {
+    pub fn is_compatible(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns the first blocking issue, if any.
+    pub fn into_result(self) -> Result<(), GeneratorError> {
+        match self.issues.into_iter().next() {
+            Some(issue) => Err(issue),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for CompatibilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "FRI reduction arity bits {:?}, cap height {}, zero knowledge {}",
+            self.reduction_arity_bits, self.cap_height, self.zero_knowledge
+        )?;
+        if self.issues.is_empty() {
+            return writeln!(f, "compatible");
+        }
+        for issue in &self.issues {
+            writeln!(f, "- {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks that a Solidity verifier can be generated for the circuit with the built-in gates.
+pub fn check_evm_compatibility<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    common: &CommonCircuitData<F, C, D>,
+) -> CompatibilityReport {
+    check_evm_compatibility_with_gates(common, &GateRegistry::default())
+}
+
+/// Same as `check_evm_compatibility`, with the gates supported by `gates`.
+pub fn check_evm_compatibility_with_gates<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    common: &CommonCircuitData<F, C, D>,
+    gates: &GateRegistry<F, D>,
+) -> CompatibilityReport {
+    let mut issues = Vec::new();
+
+    if C::Hasher::HASH_SIZE != 25 {
+        issues.push(GeneratorError::UnsupportedHasher {
+            hash_size: C::Hasher::HASH_SIZE,
+        });
+    }
+    if F::BITS != 64 || F::Extension::BITS != 128 {
+        issues.push(GeneratorError::UnsupportedField {
+            bits: F::BITS,
+            extension_bits: F::Extension::BITS,
+        });
+    }
+    // Extension elements are `uint64[2]` in Solidity.
+    if D != 2 {
+        issues.push(GeneratorError::UnsupportedExtensionDegree(D));
+    }
+    if let Some(issue) = fri_shape_issue(common) {
+        issues.push(issue);
+    }
+    for gate in &common.gates {
+        // NoopGate has no constraints and is skipped by the generator.
+        if gate.0.id() != "NoopGate" && gates.get(gate).is_none() {
+            issues.push(GeneratorError::UnsupportedGate(gate.0.id()));
+        }
+    }
+
+    CompatibilityReport {
+        issues,
+        reduction_arity_bits: common.fri_params.reduction_arity_bits.clone(),
+        cap_height: common.config.fri_config.cap_height,
+        zero_knowledge: common.config.zero_knowledge,
+    }
+}
+
+/// FRI parameters whose proofs cannot be laid out, i.e. with Merkle proofs of negative length, or
+/// whose verifier would declare zero-length arrays.
+pub(crate) fn fri_shape_issue<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    common: &CommonCircuitData<F, C, D>,
+) -> Option<GeneratorError> {
+    let reduction_arity_bits = &common.fri_params.reduction_arity_bits;
+    let cap_height = common.config.fri_config.cap_height;
+    let lde_bits = common.fri_params.lde_bits();
+    if common.config.fri_config.num_query_rounds == 0 {
+        return Some(GeneratorError::UnsupportedFriShape(
+            "no FRI query rounds".to_owned(),
+        ));
+    }
+    if reduction_arity_bits.is_empty() {
+        return Some(GeneratorError::UnsupportedFriShape(
+            "no FRI reduction steps".to_owned(),
+        ));
+    }
+    if reduction_arity_bits.contains(&0) {
+        return Some(GeneratorError::UnsupportedFriShape(format!(
+            "reduction arity bits {:?} contain an arity of 1",
+            reduction_arity_bits
+        )));
+    }
+    let total_arity_bits: usize = reduction_arity_bits.iter().sum();
+    if lde_bits < total_arity_bits + cap_height {
+        return Some(GeneratorError::UnsupportedFriShape(format!(
+            "cap height {} and reduction arity bits {:?} exceed the LDE size 2^{}",
+            cap_height, reduction_arity_bits, lde_bits
+        )));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::gates::noop::NoopGate;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use crate::compatibility::{check_evm_compatibility, check_evm_compatibility_with_gates};
+    use crate::config::KeccakGoldilocksConfig2;
+    use crate::error::GeneratorError;
+    use crate::gates::GateRegistry;
+
+    #[test]
+    fn test_check_evm_compatibility() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig {
+            zero_knowledge: true,
+            ..CircuitConfig::standard_recursion_config()
+        };
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        builder.add_gate(NoopGate, vec![]);
+        let common = builder.build::<KC2>().common;
+        let report = check_evm_compatibility(&common);
+        assert!(report.is_compatible(), "{}", report);
+        assert!(report.zero_knowledge);
+
+        let report = check_evm_compatibility_with_gates(&common, &GateRegistry::new());
+        assert!(!report.is_compatible());
+        assert!(report
+            .issues
+            .iter()
+            .all(|issue| matches!(issue, GeneratorError::UnsupportedGate(_))));
+
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        builder.add_gate(NoopGate, vec![]);
+        let common = builder.build::<C>().common;
+        let report = check_evm_compatibility(&common);
+        assert!(matches!(
+            report.into_result(),
+            Err(GeneratorError::UnsupportedHasher { hash_size: 32 })
+        ));
+    }
+}
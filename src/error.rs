@@ -0,0 +1,121 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Errors returned by the verifier generator for circuits and proofs it cannot handle.
+#[derive(Debug)]
+pub enum GeneratorError {
+    /// No Solidity code is registered for the gate with this id.
+    UnsupportedGate(String),
+    /// The Merkle hash output is not 25 bytes, i.e. not `KeccakHash<25>`.
+    UnsupportedHasher { hash_size: usize },
+    /// The base field is not 64 bits or its extension not 128 bits.
+    UnsupportedField { bits: usize, extension_bits: usize },
+    /// Extension elements are represented as `uint64[2]`, so only quadratic extensions are supported.
+    UnsupportedExtensionDegree(usize),
+    /// The FRI parameters cannot be laid out in a proof.
+    UnsupportedFriShape(String),
+    /// A section of the proof does not have the size computed from the `VerifierConfig`.
+    LayoutMismatch {
+        section: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    /// A field of a loaded `VerifierConfig` is unsupported, or differs from the one derived from
+    /// the circuit.
+    ConfigMismatch {
+        field: String,
+        expected: String,
+        actual: String,
+    },
+    /// A value was set for a placeholder the template does not contain.
+    UnknownPlaceholder {
+        template: String,
+        placeholder: String,
+    },
+    /// Placeholders left in a rendered template, e.g. misspelled or not set by the generator.
+    UnreplacedPlaceholders {
+        template: String,
+        placeholders: Vec<String>,
+    },
+    /// A Solidity template could not be read.
+    TemplateIo {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorError::UnsupportedGate(id) => write!(f, "gate not implemented: {}", id),
+            GeneratorError::UnsupportedHasher { hash_size } => write!(
+                f,
+                "unsupported hash size {}, only KeccakHash<25> is supported",
+                hash_size
+            ),
+            GeneratorError::UnsupportedField {
+                bits,
+                extension_bits,
+            } => write!(
+                f,
+                "unsupported field of {} bits with a {} bits extension, expected 64 and 128",
+                bits, extension_bits
+            ),
+            GeneratorError::UnsupportedExtensionDegree(d) => write!(
+                f,
+                "unsupported extension degree {}, only quadratic extensions are supported",
+                d
+            ),
+            GeneratorError::UnsupportedFriShape(reason) => {
+                write!(f, "unsupported FRI shape: {}", reason)
+            }
+            GeneratorError::LayoutMismatch {
+                section,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} is {} bytes, expected {} bytes",
+                section, actual, expected
+            ),
+            GeneratorError::ConfigMismatch {
+                field,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "verifier config {} is {}, expected {}",
+                field, actual, expected
+            ),
+            GeneratorError::UnknownPlaceholder {
+                template,
+                placeholder,
+            } => write!(
+                f,
+                "template {} has no placeholder ${}",
+                template, placeholder
+            ),
+            GeneratorError::UnreplacedPlaceholders {
+                template,
+                placeholders,
+            } => write!(
+                f,
+                "template {} has unreplaced placeholders: ${}",
+                template,
+                placeholders.join(", $")
+            ),
+            GeneratorError::TemplateIo { path, source } => {
+                write!(f, "failed to read template {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GeneratorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GeneratorError::TemplateIo { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
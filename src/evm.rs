@@ -0,0 +1,158 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{anyhow, bail, Context, Result};
+use revm::{
+    CreateScheme, ExecutionResult, InMemoryDB, Return, SpecId, TransactOut, TransactTo, B160, U256,
+};
+
+use crate::calldata::{encode_calldata, VerifierFunction};
+use crate::project::STATIC_CONTRACTS;
+
+/// Gas limit of the deployment and verification transactions, as in `hardhat.config.ts`.
+const GAS_LIMIT: u64 = 3_000_000_000;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct EvmVerification {
+    /// `verify(bytes)` returned true. False if it returned false or reverted.
+    pub verified: bool,
+    /// Gas used by the transaction calling `verify(bytes)`, including calldata.
+    pub gas_used: u64,
+}
+
+/// Compiles the generated contracts in `build_dir` with the `solc` binary, or the one set by the
+/// `SOLC` environment variable, and returns the creation bytecode of `Plonky2Verifier`.
+pub fn compile_verifier<P: AsRef<Path>>(
+    build_dir: P,
+    contract: &str,
+    gates_lib: &str,
+    proof_lib: &str,
+) -> Result<Vec<u8>> {
+    let build_dir = build_dir.as_ref();
+    std::fs::create_dir_all(build_dir)?;
+    for (file_name, code) in STATIC_CONTRACTS {
+        std::fs::write(build_dir.join(file_name), code)?;
+    }
+    std::fs::write(build_dir.join("Verifier.sol"), contract)?;
+    std::fs::write(build_dir.join("GatesLib.sol"), gates_lib)?;
+    std::fs::write(build_dir.join("ProofLib.sol"), proof_lib)?;
+
+    let solc = std::env::var("SOLC").unwrap_or_else(|_| "solc".to_owned());
+    let output = Command::new(&solc)
+        .current_dir(build_dir)
+        .args([
+            "--optimize",
+            "--combined-json",
+            "bin",
+            "--base-path",
+            ".",
+            "Verifier.sol",
+        ])
+        .output()
+        .with_context(|| format!("failed to run {}", solc))?;
+    if !output.status.success() {
+        bail!(
+            "solc failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let bin = json["contracts"]["Verifier.sol:Plonky2Verifier"]["bin"]
+        .as_str()
+        .ok_or_else(|| anyhow!("solc output has no Plonky2Verifier bytecode"))?;
+    decode_hex(bin)
+}
+
+/// Deploys `bytecode` in an empty in-memory EVM and calls `verify(proof)`.
+pub fn execute_verify(bytecode: &[u8], proof: &[u8]) -> Result<EvmVerification> {
+    let mut evm = revm::new();
+    evm.database(InMemoryDB::default());
+    evm.env.cfg.spec_id = SpecId::LONDON;
+    evm.env.cfg.limit_contract_code_size = Some(usize::MAX);
+    evm.env.block.gas_limit = U256::MAX;
+    evm.env.tx.caller = B160::repeat_byte(1);
+    evm.env.tx.gas_limit = GAS_LIMIT;
+    evm.env.tx.transact_to = TransactTo::Create(CreateScheme::Create);
+    evm.env.tx.data = bytecode.to_vec().into();
+
+    let address = match evm.transact_commit() {
+        ExecutionResult {
+            exit_reason: Return::Return | Return::Stop,
+            out: TransactOut::Create(_, Some(address)),
+            ..
+        } => address,
+        result => bail!("deployment failed: {:?}", result.exit_reason),
+    };
+
+    evm.env.tx.transact_to = TransactTo::Call(address);
+    evm.env.tx.data = encode_calldata(VerifierFunction::Verify, proof).into();
+    let ExecutionResult {
+        exit_reason,
+        out,
+        gas_used,
+        ..
+    } = evm.transact_commit();
+    Ok(match (exit_reason, out) {
+        (Return::Return | Return::Stop, TransactOut::Call(output)) => EvmVerification {
+            verified: output.len() == 32 && output[31] == 1,
+            gas_used,
+        },
+        (Return::Return | Return::Stop, out) => {
+            bail!("unexpected verification output: {:?}", out)
+        }
+        _ => EvmVerification {
+            verified: false,
+            gas_used,
+        },
+    })
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>> {
+    let s = s.trim_start_matches("0x");
+    if s.len() % 2 != 0 {
+        bail!("odd length hex string");
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|e| e.into()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::plonk::config::GenericConfig;
+
+    use crate::config::KeccakGoldilocksConfig2;
+    use crate::evm::{compile_verifier, execute_verify};
+    use crate::test_utils::dummy_proof;
+    use crate::verifier::{generate_solidity_verifier, generate_verifier_config};
+    use crate::wrap::final_config;
+
+    #[test]
+    fn test_execute_verify() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+
+        let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config(), 4_000, 4)?;
+        let conf = generate_verifier_config(&proof)?;
+        let (contract, gates_lib, proof_lib) = generate_solidity_verifier(&conf, &cd, &vd)?;
+        let build_dir = std::env::temp_dir().join("plonky2_solidity_evm");
+        let bytecode = compile_verifier(&build_dir, &contract, &gates_lib, &proof_lib)?;
+
+        let mut proof_bytes = proof.to_bytes()?;
+        let result = execute_verify(&bytecode, &proof_bytes)?;
+        assert!(result.verified);
+        assert!(result.gas_used > 0);
+
+        // Tampering with a public input must fail the verification.
+        let last = proof_bytes.len() - 1;
+        proof_bytes[last] ^= 1;
+        assert!(!execute_verify(&bytecode, &proof_bytes)?.verified);
+
+        std::fs::remove_dir_all(&build_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::plonk::config::GenericConfig;
+use serde::Serialize;
+
+use crate::verifier::{proof_size, VerifierConfig};
+
+// Unit costs of the generated verifier. `test_estimate_verification_gas_in_evm` checks that the
+// estimates are within 25% of the gas used by `execute_verify` in the in-process EVM, on FRI
+// shapes with two steps of arity 8, three steps of arity 8 and two steps of arity 32.
+
+const TX_BASE_GAS: u64 = 21_000;
+/// Gas per calldata byte, assuming the proof bytes are non-zero.
+const CALLDATA_BYTE_GAS: u64 = 16;
+/// One extension field addition or multiplication, including its memory allocation.
+const EXT_OP_GAS: u64 = 900;
+/// One field inversion, computed as an exponentiation.
+const INVERSE_GAS: u64 = 35_000;
+/// Observing or squeezing one field element in the Keccak challenger.
+const CHALLENGER_ELEMENT_GAS: u64 = 1_500;
+/// One `keccak256` of two caps in a Merkle path, including the calldata copies.
+const MERKLE_HASH_GAS: u64 = 1_000;
+/// Hashing a Merkle leaf, plus `LEAF_HASH_WORD_GAS` per 32 bytes of leaf.
+const LEAF_HASH_GAS: u64 = 500;
+const LEAF_HASH_WORD_GAS: u64 = 12;
+/// Extension field operations per gate constraint.
+const GATE_CONSTRAINT_OPS: u64 = 6;
+/// Field elements in a serialized cap element.
+const HASH_ELEMENTS: u64 = 4;
+
+/// Estimated gas of `execute_verify`, broken down by verification phase.
+#[derive(Serialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct GasEstimate {
+    /// Transaction base cost and proof calldata.
+    pub calldata: u64,
+    /// Observing the proof in the Keccak challenger and squeezing the challenges.
+    pub challenger: u64,
+    /// Permutation and partial products checks of the vanishing polynomial.
+    pub vanishing_polynomial: u64,
+    /// Evaluation of the gate constraints.
+    pub gate_constraints: u64,
+    /// Leaf hashes and Merkle paths of every FRI query round.
+    pub fri_merkle_paths: u64,
+    /// Opening reductions, coset interpolations and final polynomial evaluations.
+    pub fri_folding: u64,
+}
+
+impl GasEstimate {
+    pub fn total(&self) -> u64 {
+        self.calldata
+            + self.challenger
+            + self.vanishing_polynomial
+            + self.gate_constraints
+            + self.fri_merkle_paths
+            + self.fri_folding
+    }
+}
+
+/// Estimates the gas of verifying proofs described by `conf` with the generated verifier.
+pub fn estimate_verification_gas<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    conf: &VerifierConfig,
+    common: &CommonCircuitData<F, C, D>,
+) -> GasEstimate {
+    let num_challenges = common.config.num_challenges as u64;
+    let num_rounds = conf.num_fri_query_round as u64;
+    let num_steps = conf.num_fri_query_step_v.len() as u64;
+    let num_openings = (conf.num_openings_constants
+        + conf.num_openings_plonk_sigmas
+        + conf.num_openings_wires
+        + conf.num_openings_plonk_zs
+        + conf.num_openings_plonk_zs_next
+        + conf.num_openings_partial_products
+        + conf.num_openings_quotient_polys) as u64;
+
+    let calldata = TX_BASE_GAS + proof_size(conf) as u64 * CALLDATA_BYTE_GAS;
+
+    let num_caps = (conf.num_wires_cap
+        + conf.num_plonk_zs_partial_products_cap
+        + conf.num_quotient_polys_cap
+        + conf.num_fri_commit_round * conf.fri_commit_merkle_cap_height) as u64;
+    // Circuit digest and public inputs hash, then the proof.
+    let observed = 2 * HASH_ELEMENTS
+        + num_caps * HASH_ELEMENTS
+        + num_openings * D as u64
+        + conf.num_fri_final_poly_ext_v as u64 * D as u64
+        + 1;
+    // Betas, gammas, alphas, zeta, the FRI alpha and betas, and the query indices.
+    let squeezed = 3 * num_challenges + (1 + 1 + num_steps) * D as u64 + num_rounds;
+    let challenger = (observed + squeezed) * CHALLENGER_ELEMENT_GAS;
+
+    let vanishing_ops = num_challenges
+        * (4 * conf.num_openings_plonk_sigmas as u64
+            + 3 * common.num_partial_products as u64
+            + 2 * common.quotient_degree_factor as u64
+            + 10)
+        + common.degree_bits as u64;
+    // L_1(zeta) and the division by Z_H(zeta).
+    let vanishing_polynomial = vanishing_ops * EXT_OP_GAS + 2 * INVERSE_GAS;
+
+    let gate_ops: u64 = common
+        .gates
+        .iter()
+        .enumerate()
+        .filter(|(_, gate)| gate.0.id() != "NoopGate")
+        .map(|(i, gate)| {
+            // Each gate is filtered by the selector polynomial of its group.
+            let group = &common.selectors_info.groups[common.selectors_info.selector_indices[i]];
+            gate.0.num_constraints() as u64 * GATE_CONSTRAINT_OPS + group.len() as u64
+        })
+        .sum::<u64>()
+        + 2 * num_challenges * common.num_gate_constraints as u64;
+    let gate_constraints = gate_ops * EXT_OP_GAS;
+
+    let init_leaves = [
+        conf.num_fri_query_init_constants_sigmas_v,
+        conf.num_fri_query_init_wires_v,
+        conf.num_fri_query_init_zs_partial_v,
+        conf.num_fri_query_init_quotient_v,
+    ]
+    .map(|v| (v * conf.field_size) as u64);
+    let step_leaves = conf
+        .num_fri_query_step_v
+        .iter()
+        .map(|v| (v * conf.ext_field_size) as u64);
+    let leaves_gas: u64 = init_leaves
+        .into_iter()
+        .chain(step_leaves)
+        .map(|bytes| LEAF_HASH_GAS + (bytes + 31) / 32 * LEAF_HASH_WORD_GAS)
+        .sum();
+    let path_hashes = (conf.num_fri_query_init_constants_sigmas_p
+        + conf.num_fri_query_init_wires_p
+        + conf.num_fri_query_init_zs_partial_p
+        + conf.num_fri_query_init_quotient_p
+        + conf.num_fri_query_step_p.iter().sum::<usize>()) as u64;
+    let fri_merkle_paths = num_rounds * (leaves_gas + path_hashes * MERKLE_HASH_GAS);
+
+    // The openings are reduced once, the initial tree values once per round.
+    let reduce1_ops = 2 * num_openings;
+    let unsalted_values = (conf.num_fri_query_init_constants_sigmas_v
+        + conf.num_fri_query_init_wires_v
+        + conf.num_fri_query_init_zs_partial_v
+        + conf.num_fri_query_init_quotient_v
+        - 3 * conf.salt_size) as u64;
+    let lde_bits = common.fri_params.lde_bits() as u64;
+    let mut round_ops = 2 * unsalted_values + 2 * num_challenges + 2 * lde_bits;
+    // Two divisions by `subgroup_x - zeta` and `subgroup_x - g * zeta`.
+    let mut round_inverses = 2;
+    for &arity in &conf.num_fri_query_step_v {
+        let arity = arity as u64;
+        // Barycentric weights and interpolation of the coset.
+        round_ops += arity * arity + 3 * arity;
+        round_inverses += arity + 1;
+    }
+    round_ops += 2 * conf.num_fri_final_poly_ext_v as u64;
+    let fri_folding = reduce1_ops * EXT_OP_GAS
+        + num_rounds * (round_ops * EXT_OP_GAS + round_inverses * INVERSE_GAS);
+
+    GasEstimate {
+        calldata,
+        challenger,
+        vanishing_polynomial,
+        gate_constraints,
+        fri_merkle_paths,
+        fri_folding,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::fri::reduction_strategies::FriReductionStrategy;
+    use plonky2::fri::FriConfig;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::GenericConfig;
+
+    use crate::config::KeccakGoldilocksConfig2;
+    use crate::gas::estimate_verification_gas;
+    use crate::test_utils::dummy_circuit;
+    use crate::verifier::{generate_verifier_config_from_common_data, proof_size};
+    use crate::wrap::final_config;
+
+    /// `final_config` with a fixed FRI reduction, as in the verifier tests.
+    fn final_config_with_arities(reduction_strategy: FriReductionStrategy) -> CircuitConfig {
+        let config = final_config();
+        CircuitConfig {
+            fri_config: FriConfig {
+                reduction_strategy,
+                ..config.fri_config.clone()
+            },
+            ..config
+        }
+    }
+
+    #[test]
+    fn test_estimate_verification_gas() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+
+        // The dummy proof of `test_verifier_without_public_inputs`.
+        let cd = dummy_circuit::<F, KC2, D>(&final_config(), 4_000, 0).common;
+        let conf = generate_verifier_config_from_common_data(&cd)?;
+        assert_eq!(proof_size(&conf), 50855);
+        let estimate = estimate_verification_gas(&conf, &cd);
+        assert!(estimate.fri_folding > estimate.fri_merkle_paths);
+
+        // A third step adds a Merkle path and a coset interpolation to every query round.
+        let config = final_config_with_arities(FriReductionStrategy::ConstantArityBits(3, 5));
+        let cd = dummy_circuit::<F, KC2, D>(&config, 4_000, 0).common;
+        assert_eq!(cd.fri_params.reduction_arity_bits, vec![3, 3, 3]);
+        let conf = generate_verifier_config_from_common_data(&cd)?;
+        let three_steps = estimate_verification_gas(&conf, &cd);
+        assert!(three_steps.fri_merkle_paths > estimate.fri_merkle_paths);
+        assert!(three_steps.fri_folding > estimate.fri_folding);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn test_estimate_verification_gas_in_evm() -> Result<()> {
+        use crate::evm::{compile_verifier, execute_verify};
+        use crate::test_utils::dummy_proof;
+        use crate::verifier::{generate_solidity_verifier, generate_verifier_config};
+
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+
+        let build_dir = std::env::temp_dir().join("plonky2_solidity_gas");
+        for (config, arity_bits) in [
+            (final_config(), vec![3, 3]),
+            (
+                final_config_with_arities(FriReductionStrategy::ConstantArityBits(3, 5)),
+                vec![3, 3, 3],
+            ),
+            (
+                final_config_with_arities(FriReductionStrategy::ConstantArityBits(5, 2)),
+                vec![5, 5],
+            ),
+        ] {
+            let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&config, 4_000, 0)?;
+            assert_eq!(cd.fri_params.reduction_arity_bits, arity_bits);
+            let conf = generate_verifier_config(&proof)?;
+            let (contract, gates_lib, proof_lib) = generate_solidity_verifier(&conf, &cd, &vd)?;
+            let bytecode = compile_verifier(&build_dir, &contract, &gates_lib, &proof_lib)?;
+            let result = execute_verify(&bytecode, &proof.to_bytes()?)?;
+            assert!(result.verified);
+
+            let total = estimate_verification_gas(&conf, &cd).total();
+            assert!(
+                total > result.gas_used * 3 / 4 && total < result.gas_used * 5 / 4,
+                "arity bits {:?}: measured {} gas, estimated {}",
+                arity_bits,
+                result.gas_used,
+                total
+            );
+        }
+
+        std::fs::remove_dir_all(&build_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use plonky2::field::extension::Extendable;
+use plonky2::gates::gate::GateRef;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_data::CircuitConfig;
+
+use crate::gates::poseidon::PoseidonSolidityGate;
+use crate::gates::poseidon_mds::PoseidonMdsSolidityGate;
+use crate::solidity::Expr;
+
+pub mod poseidon;
+pub mod poseidon_mds;
+
+/// Emits the Solidity library evaluating the constraints of a gate.
+///
+/// The returned code must start with `library <Name> {` and define
+/// `set_filter(GatesUtilsLib.EvaluationVars memory ev)`, whose body is the `$SET_FILTER;`
+/// placeholder, and `eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints)`.
+pub trait SolidityGate<F: RichField + Extendable<D>, const D: usize>: Send + Sync {
+    fn export_solidity_verification_code(&self, gate: &GateRef<F, D>) -> String;
+
+    /// The parameters of `gate` saved in `SerializedCircuitData`, for gates that are not built into
+    /// it. `None` to save `gate` as a built-in gate.
+    fn serialize_gate(&self, _gate: &GateRef<F, D>) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Rebuilds a gate from the parameters returned by `serialize_gate`.
+    fn deserialize_gate(&self, _params: &[u8], _config: &CircuitConfig) -> Option<GateRef<F, D>> {
+        None
+    }
+}
+
+/// Gates whose Solidity code is exported by plonky2 itself.
+pub struct ExportedSolidityGate;
+
+impl<F: RichField + Extendable<D>, const D: usize> SolidityGate<F, D> for ExportedSolidityGate {
+    fn export_solidity_verification_code(&self, gate: &GateRef<F, D>) -> String {
+        //TODO: use num_coeff as a param (same TODO for other gates)
+        gate.0.export_solidity_verification_code()
+    }
+}
+
+/// Solidity emitters keyed by gate type, i.e. the gate id up to its parameters.
+pub struct GateRegistry<F: RichField + Extendable<D>, const D: usize> {
+    gates: HashMap<String, Box<dyn SolidityGate<F, D>>>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> GateRegistry<F, D> {
+    /// An empty registry, see `GateRegistry::default` for one with the built-in gates.
+    pub fn new() -> Self {
+        Self {
+            gates: HashMap::new(),
+        }
+    }
+
+    /// Registers `gate` as the emitter for `gate_type`, replacing any previous one.
+    pub fn register<G: SolidityGate<F, D> + 'static>(&mut self, gate_type: &str, gate: G) {
+        self.gates.insert(gate_type.to_owned(), Box::new(gate));
+    }
+
+    pub fn get(&self, gate: &GateRef<F, D>) -> Option<&dyn SolidityGate<F, D>> {
+        self.get_type(gate_type(&gate.0.id()))
+    }
+
+    pub fn get_type(&self, gate_type: &str) -> Option<&dyn SolidityGate<F, D>> {
+        self.gates.get(gate_type).map(|g| g.as_ref())
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Default for GateRegistry<F, D> {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        for gate_type in [
+            "PublicInputGate",
+            "BaseSumGate",
+            "ConstantGate",
+            "ReducingGate",
+            "ArithmeticGate",
+            "MulExtensionGate",
+            "RandomAccessGate",
+            "U32ArithmeticGate",
+            "ExponentiationGate",
+            "ReducingExtensionGate",
+            "ArithmeticExtensionGate",
+            "LowDegreeInterpolationGate",
+        ] {
+            registry.register(gate_type, ExportedSolidityGate);
+        }
+        registry.register("PoseidonGate", PoseidonSolidityGate);
+        registry.register("PoseidonMdsGate", PoseidonMdsSolidityGate);
+        registry
+    }
+}
+
+/// Strips the parameters from a gate id, e.g. `ArithmeticGate { num_ops: 20 }` gives `ArithmeticGate`.
+pub fn gate_type(gate_id: &str) -> &str {
+    let end = gate_id
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(gate_id.len());
+    &gate_id[..end]
+}
+
+/// Formats `values` as a Solidity `uint64[N]` array literal.
+pub(crate) fn u64_array_literal(values: &[u64]) -> String {
+    Expr::u64_array(values).to_string()
+}
+
+/// Formats `rows` as a Solidity `uint64[M][N]` array literal, one row per line.
+pub(crate) fn u64_matrix_literal<R: AsRef<[u64]>>(rows: &[R], indent: &str) -> String {
+    let mut s = "[\n".to_owned();
+    for (i, row) in rows.iter().enumerate() {
+        s += &*(indent.to_owned() + "    " + &*u64_array_literal(row.as_ref()));
+        if i + 1 < rows.len() {
+            s += ",";
+        }
+        s += "\n";
+    }
+    s + indent + "]"
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::gates::arithmetic_base::ArithmeticGate;
+    use plonky2::gates::gate::GateRef;
+    use plonky2::gates::noop::NoopGate;
+    use plonky2::gates::poseidon::PoseidonGate;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use crate::gates::{gate_type, GateRegistry, SolidityGate};
+
+    #[test]
+    fn test_gate_type() {
+        assert_eq!(gate_type("PublicInputGate"), "PublicInputGate");
+        assert_eq!(
+            gate_type("ArithmeticGate { num_ops: 20 }"),
+            "ArithmeticGate"
+        );
+        assert_eq!(
+            gate_type("PoseidonGate(PhantomData)<WIDTH=12>"),
+            "PoseidonGate"
+        );
+    }
+
+    #[test]
+    fn test_gate_registry() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        struct CustomSolidityGate;
+        impl SolidityGate<F, D> for CustomSolidityGate {
+            fn export_solidity_verification_code(&self, _: &GateRef<F, D>) -> String {
+                "library NoopGateLib {}".to_owned()
+            }
+        }
+
+        let mut registry = GateRegistry::<F, D>::default();
+        let config = CircuitConfig::standard_recursion_config();
+        assert!(registry
+            .get(&GateRef::new(ArithmeticGate::new_from_config(&config)))
+            .is_some());
+        let poseidon = GateRef::new(PoseidonGate::<F, D>::new());
+        assert!(registry
+            .get(&poseidon)
+            .unwrap()
+            .export_solidity_verification_code(&poseidon)
+            .starts_with("library PoseidonGateLib {"));
+
+        let noop = GateRef::new(NoopGate);
+        assert!(registry.get(&noop).is_none());
+        registry.register("NoopGate", CustomSolidityGate);
+        assert_eq!(
+            registry
+                .get(&noop)
+                .unwrap()
+                .export_solidity_verification_code(&noop),
+            "library NoopGateLib {}"
+        );
+    }
+}
@@ -0,0 +1,361 @@
+use plonky2::field::extension::Extendable;
+use plonky2::gates::gate::GateRef;
+use plonky2::gates::poseidon::PoseidonGate;
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::hashing::SPONGE_WIDTH;
+use plonky2::hash::poseidon::{
+    Poseidon, ALL_ROUND_CONSTANTS, HALF_N_FULL_ROUNDS, N_PARTIAL_ROUNDS,
+};
+
+use crate::gates::{u64_array_literal, u64_matrix_literal, SolidityGate};
+
+const POSEIDON_GATE_TEMPLATE: &str = "library PoseidonGateLib {
+    using GoldilocksFieldLib for uint64;
+    using GoldilocksExtLib for uint64[2];
+
+    uint32 constant SPONGE_WIDTH = $SPONGE_WIDTH;
+    uint32 constant HALF_N_FULL_ROUNDS = $HALF_N_FULL_ROUNDS;
+    uint32 constant N_PARTIAL_ROUNDS = $N_PARTIAL_ROUNDS;
+
+    uint32 constant WIRE_INPUT = $WIRE_INPUT;
+    uint32 constant WIRE_OUTPUT = $WIRE_OUTPUT;
+    uint32 constant WIRE_SWAP = $WIRE_SWAP;
+    uint32 constant WIRE_DELTA = $WIRE_DELTA;
+    uint32 constant WIRE_FULL_SBOX_0 = $WIRE_FULL_SBOX_0;
+    uint32 constant WIRE_PARTIAL_SBOX = $WIRE_PARTIAL_SBOX;
+    uint32 constant WIRE_FULL_SBOX_1 = $WIRE_FULL_SBOX_1;
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function full_round_constants() internal pure returns (uint64[SPONGE_WIDTH][2 * HALF_N_FULL_ROUNDS] memory) {
+        return $FULL_ROUND_CONSTANTS;
+    }
+
+    function fast_partial_round_vs() internal pure returns (uint64[SPONGE_WIDTH - 1][N_PARTIAL_ROUNDS] memory) {
+        return $FAST_PARTIAL_ROUND_VS;
+    }
+
+    function fast_partial_round_w_hats() internal pure returns (uint64[SPONGE_WIDTH - 1][N_PARTIAL_ROUNDS] memory) {
+        return $FAST_PARTIAL_ROUND_W_HATS;
+    }
+
+    function sbox_monomial(uint64[2] memory x) internal pure returns (uint64[2] memory) {
+        uint64[2] memory x2 = x.square();
+        uint64[2] memory x4 = x2.square();
+        uint64[2] memory x3 = x.mul(x2);
+        return x3.mul(x4);
+    }
+
+    function constant_layer(uint64[2][SPONGE_WIDTH] memory state, uint64[SPONGE_WIDTH] memory round_constants) internal pure {
+        for (uint32 i = 0; i < SPONGE_WIDTH; i++) {
+            state[i][0] = state[i][0].add(round_constants[i]);
+        }
+    }
+
+    function sbox_layer(uint64[2][SPONGE_WIDTH] memory state) internal pure {
+        for (uint32 i = 0; i < SPONGE_WIDTH; i++) {
+            state[i] = sbox_monomial(state[i]);
+        }
+    }
+
+    function mds_layer(uint64[2][SPONGE_WIDTH] memory state) internal pure returns (uint64[2][SPONGE_WIDTH] memory res) {
+        uint64[SPONGE_WIDTH] memory circ = $MDS_MATRIX_CIRC;
+        uint64[SPONGE_WIDTH] memory diag = $MDS_MATRIX_DIAG;
+        for (uint32 r = 0; r < SPONGE_WIDTH; r++) {
+            for (uint32 i = 0; i < SPONGE_WIDTH; i++) {
+                res[r] = res[r].add(state[(i + r) % SPONGE_WIDTH].scalar_mul(circ[i]));
+            }
+            res[r] = res[r].add(state[r].scalar_mul(diag[r]));
+        }
+    }
+
+    function mds_partial_layer_init(uint64[2][SPONGE_WIDTH] memory state) internal pure returns (uint64[2][SPONGE_WIDTH] memory res) {
+        uint64[SPONGE_WIDTH - 1][SPONGE_WIDTH - 1] memory m = $FAST_PARTIAL_ROUND_INITIAL_MATRIX;
+        res[0] = state[0];
+        for (uint32 r = 1; r < SPONGE_WIDTH; r++) {
+            for (uint32 c = 1; c < SPONGE_WIDTH; c++) {
+                res[c] = res[c].add(state[r].scalar_mul(m[r - 1][c - 1]));
+            }
+        }
+    }
+
+    function mds_partial_layer_fast(uint64[2][SPONGE_WIDTH] memory state, uint64[SPONGE_WIDTH - 1] memory vs,
+        uint64[SPONGE_WIDTH - 1] memory w_hats) internal pure returns (uint64[2][SPONGE_WIDTH] memory res) {
+        res[0] = state[0].scalar_mul($MDS_MATRIX_0_TO_0);
+        for (uint32 i = 1; i < SPONGE_WIDTH; i++) {
+            res[0] = res[0].add(state[i].scalar_mul(w_hats[i - 1]));
+        }
+        for (uint32 i = 1; i < SPONGE_WIDTH; i++) {
+            res[i] = state[0].scalar_mul(vs[i - 1]).add(state[i]);
+        }
+    }
+
+    function partial_rounds(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints,
+        uint64[2][SPONGE_WIDTH] memory state, uint32 index) internal pure returns (uint64[2][SPONGE_WIDTH] memory, uint32) {
+        constant_layer(state, $FAST_PARTIAL_FIRST_ROUND_CONSTANT);
+        state = mds_partial_layer_init(state);
+
+        uint64[N_PARTIAL_ROUNDS] memory round_constants = $FAST_PARTIAL_ROUND_CONSTANTS;
+        uint64[SPONGE_WIDTH - 1][N_PARTIAL_ROUNDS] memory vs = fast_partial_round_vs();
+        uint64[SPONGE_WIDTH - 1][N_PARTIAL_ROUNDS] memory w_hats = fast_partial_round_w_hats();
+        for (uint32 r = 0; r < N_PARTIAL_ROUNDS; r++) {
+            uint64[2] memory sbox_in = ev.wires[WIRE_PARTIAL_SBOX + r];
+            GatesUtilsLib.push(constraints, ev.filter, index++, state[0].sub(sbox_in));
+            state[0] = sbox_monomial(sbox_in);
+            if (r < N_PARTIAL_ROUNDS - 1) {
+                state[0][0] = state[0][0].add(round_constants[r]);
+            }
+            state = mds_partial_layer_fast(state, vs[r], w_hats[r]);
+        }
+        return (state, index);
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        uint64[2][SPONGE_WIDTH] memory state;
+        {
+            // Assert that `swap` is binary.
+            uint64[2] memory swap = ev.wires[WIRE_SWAP];
+            GatesUtilsLib.push(constraints, ev.filter, index++, swap.mul(swap.sub(GoldilocksExtLib.one())));
+
+            // Assert that each delta wire is set properly: `delta_i = swap * (rhs - lhs)`.
+            // Compute the possibly-swapped input layer at the same time.
+            for (uint32 i = 0; i < 4; i++) {
+                uint64[2] memory input_lhs = ev.wires[WIRE_INPUT + i];
+                uint64[2] memory input_rhs = ev.wires[WIRE_INPUT + i + 4];
+                uint64[2] memory delta_i = ev.wires[WIRE_DELTA + i];
+                GatesUtilsLib.push(constraints, ev.filter, index++, swap.mul(input_rhs.sub(input_lhs)).sub(delta_i));
+                state[i] = input_lhs.add(delta_i);
+                state[i + 4] = input_rhs.sub(delta_i);
+            }
+            for (uint32 i = 8; i < SPONGE_WIDTH; i++) {
+                state[i] = ev.wires[WIRE_INPUT + i];
+            }
+        }
+
+        uint64[SPONGE_WIDTH][2 * HALF_N_FULL_ROUNDS] memory rc = full_round_constants();
+
+        // First set of full rounds.
+        for (uint32 r = 0; r < HALF_N_FULL_ROUNDS; r++) {
+            constant_layer(state, rc[r]);
+            if (r != 0) {
+                for (uint32 i = 0; i < SPONGE_WIDTH; i++) {
+                    uint64[2] memory sbox_in = ev.wires[WIRE_FULL_SBOX_0 + SPONGE_WIDTH * (r - 1) + i];
+                    GatesUtilsLib.push(constraints, ev.filter, index++, state[i].sub(sbox_in));
+                    state[i] = sbox_in;
+                }
+            }
+            sbox_layer(state);
+            state = mds_layer(state);
+        }
+
+        // Partial rounds.
+        (state, index) = partial_rounds(ev, constraints, state, index);
+
+        // Second set of full rounds.
+        for (uint32 r = 0; r < HALF_N_FULL_ROUNDS; r++) {
+            constant_layer(state, rc[HALF_N_FULL_ROUNDS + r]);
+            for (uint32 i = 0; i < SPONGE_WIDTH; i++) {
+                uint64[2] memory sbox_in = ev.wires[WIRE_FULL_SBOX_1 + SPONGE_WIDTH * r + i];
+                GatesUtilsLib.push(constraints, ev.filter, index++, state[i].sub(sbox_in));
+                state[i] = sbox_in;
+            }
+            sbox_layer(state);
+            state = mds_layer(state);
+        }
+
+        for (uint32 i = 0; i < SPONGE_WIDTH; i++) {
+            GatesUtilsLib.push(constraints, ev.filter, index++, state[i].sub(ev.wires[WIRE_OUTPUT + i]));
+        }
+    }
+}
+";
+
+// Wire layout of `PoseidonGate` after its swap wire, whose accessors are private in plonky2: the
+// deltas of the swap, then the S-box inputs of the first full rounds but the first one, of the
+// partial rounds and of the second full rounds.
+const WIRE_DELTA: usize = 2 * SPONGE_WIDTH + 1;
+const WIRE_FULL_SBOX_0: usize = WIRE_DELTA + 4;
+const WIRE_PARTIAL_SBOX: usize = WIRE_FULL_SBOX_0 + SPONGE_WIDTH * (HALF_N_FULL_ROUNDS - 1);
+const WIRE_FULL_SBOX_1: usize = WIRE_PARTIAL_SBOX + N_PARTIAL_ROUNDS;
+
+/// Solidity evaluation of `PoseidonGate` constraints, mirroring `PoseidonGate::eval_unfiltered`.
+/// The round constants and MDS matrices are taken from the `Poseidon` implementation of `F`.
+pub fn poseidon_gate_solidity_code<F: RichField + Extendable<D>, const D: usize>() -> String {
+    let full_round_constants = (0..HALF_N_FULL_ROUNDS)
+        .chain(HALF_N_FULL_ROUNDS + N_PARTIAL_ROUNDS..2 * HALF_N_FULL_ROUNDS + N_PARTIAL_ROUNDS)
+        .map(|r| ALL_ROUND_CONSTANTS[r * SPONGE_WIDTH..(r + 1) * SPONGE_WIDTH].to_vec())
+        .collect::<Vec<_>>();
+
+    let mut code_str = POSEIDON_GATE_TEMPLATE.to_owned();
+    code_str = code_str.replace("$SPONGE_WIDTH", &*SPONGE_WIDTH.to_string());
+    code_str = code_str.replace("$HALF_N_FULL_ROUNDS", &*HALF_N_FULL_ROUNDS.to_string());
+    code_str = code_str.replace("$N_PARTIAL_ROUNDS", &*N_PARTIAL_ROUNDS.to_string());
+
+    code_str = code_str.replace(
+        "$WIRE_INPUT",
+        &*PoseidonGate::<F, D>::wire_input(0).to_string(),
+    );
+    code_str = code_str.replace(
+        "$WIRE_OUTPUT",
+        &*PoseidonGate::<F, D>::wire_output(0).to_string(),
+    );
+    code_str = code_str.replace("$WIRE_SWAP", &*PoseidonGate::<F, D>::WIRE_SWAP.to_string());
+    code_str = code_str.replace("$WIRE_DELTA", &*WIRE_DELTA.to_string());
+    code_str = code_str.replace("$WIRE_FULL_SBOX_0", &*WIRE_FULL_SBOX_0.to_string());
+    code_str = code_str.replace("$WIRE_PARTIAL_SBOX", &*WIRE_PARTIAL_SBOX.to_string());
+    code_str = code_str.replace("$WIRE_FULL_SBOX_1", &*WIRE_FULL_SBOX_1.to_string());
+
+    code_str = code_str.replace(
+        "$FULL_ROUND_CONSTANTS",
+        &*u64_matrix_literal(&full_round_constants, "        "),
+    );
+    code_str = code_str.replace(
+        "$FAST_PARTIAL_ROUND_VS",
+        &*u64_matrix_literal(&<F as Poseidon>::FAST_PARTIAL_ROUND_VS, "        "),
+    );
+    code_str = code_str.replace(
+        "$FAST_PARTIAL_ROUND_W_HATS",
+        &*u64_matrix_literal(&<F as Poseidon>::FAST_PARTIAL_ROUND_W_HATS, "        "),
+    );
+    code_str = code_str.replace(
+        "$FAST_PARTIAL_ROUND_INITIAL_MATRIX",
+        &*u64_matrix_literal(
+            &<F as Poseidon>::FAST_PARTIAL_ROUND_INITIAL_MATRIX,
+            "        ",
+        ),
+    );
+    code_str = code_str.replace(
+        "$FAST_PARTIAL_FIRST_ROUND_CONSTANT",
+        &*u64_array_literal(&<F as Poseidon>::FAST_PARTIAL_FIRST_ROUND_CONSTANT),
+    );
+    code_str = code_str.replace(
+        "$FAST_PARTIAL_ROUND_CONSTANTS",
+        &*u64_array_literal(&<F as Poseidon>::FAST_PARTIAL_ROUND_CONSTANTS),
+    );
+    code_str = code_str.replace(
+        "$MDS_MATRIX_CIRC",
+        &*u64_array_literal(&<F as Poseidon>::MDS_MATRIX_CIRC),
+    );
+    code_str = code_str.replace(
+        "$MDS_MATRIX_DIAG",
+        &*u64_array_literal(&<F as Poseidon>::MDS_MATRIX_DIAG),
+    );
+    code_str = code_str.replace(
+        "$MDS_MATRIX_0_TO_0",
+        &*(<F as Poseidon>::MDS_MATRIX_CIRC[0] + <F as Poseidon>::MDS_MATRIX_DIAG[0]).to_string(),
+    );
+    code_str
+}
+
+pub struct PoseidonSolidityGate;
+
+impl<F: RichField + Extendable<D>, const D: usize> SolidityGate<F, D> for PoseidonSolidityGate {
+    fn export_solidity_verification_code(&self, _: &GateRef<F, D>) -> String {
+        poseidon_gate_solidity_code::<F, D>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Field;
+    use plonky2::gates::gate::Gate;
+    use plonky2::gates::poseidon::PoseidonGate;
+    use plonky2::hash::hashing::SPONGE_WIDTH;
+    use plonky2::hash::poseidon::{Poseidon, HALF_N_FULL_ROUNDS};
+    use plonky2::iop::generator::generate_partial_witness;
+    use plonky2::iop::wire::Wire;
+    use plonky2::iop::witness::{PartialWitness, Witness};
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use crate::gates::poseidon::{
+        poseidon_gate_solidity_code, WIRE_DELTA, WIRE_FULL_SBOX_0, WIRE_FULL_SBOX_1,
+        WIRE_PARTIAL_SBOX,
+    };
+
+    #[test]
+    fn test_poseidon_gate_solidity_code() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let code_str = poseidon_gate_solidity_code::<F, D>();
+        assert!(code_str.starts_with("library PoseidonGateLib {"));
+
+        // Only the placeholders filled by `generate_solidity_verifier` are left.
+        let leftover = code_str
+            .replace("$SET_FILTER", "")
+            .replace("$NUM_GATE_CONSTRAINTS", "");
+        assert!(!leftover.contains('$'));
+
+        // 1 swap + 4 deltas + 3 * 12 first full rounds + 22 partial + 4 * 12 second full + 12 outputs.
+        let gate = PoseidonGate::<F, D>::new();
+        assert_eq!(gate.num_constraints(), 1 + 4 + 36 + 22 + 48 + 12);
+    }
+
+    #[test]
+    fn test_poseidon_gate_wire_layout() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type Gate = PoseidonGate<F, D>;
+
+        let gate = Gate::new();
+        assert_eq!(
+            WIRE_FULL_SBOX_1 + SPONGE_WIDTH * HALF_N_FULL_ROUNDS,
+            gate.num_wires()
+        );
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let row = builder.add_gate(gate, vec![]);
+        let circuit = builder.build_prover::<C>();
+        let wire = |column| Wire { row, column };
+
+        // Swap the inputs so that the deltas are not zero.
+        let mut inputs = PartialWitness::new();
+        inputs.set_wire(wire(Gate::WIRE_SWAP), F::ONE);
+        for i in 0..SPONGE_WIDTH {
+            inputs.set_wire(wire(Gate::wire_input(i)), F::from_canonical_usize(i));
+        }
+        let witness = generate_partial_witness(inputs, &circuit.prover_only, &circuit.common);
+        for i in 0..4 {
+            assert_eq!(
+                witness.get_wire(wire(WIRE_DELTA + i)),
+                F::from_canonical_usize(4)
+            );
+        }
+        let swapped_inputs: [F; SPONGE_WIDTH] =
+            core::array::from_fn(|i| F::from_canonical_usize(if i < 8 { i ^ 4 } else { i }));
+
+        // The S-box inputs of the second round.
+        let mut state = swapped_inputs;
+        <F as Poseidon>::constant_layer(&mut state, 0);
+        <F as Poseidon>::sbox_layer(&mut state);
+        state = <F as Poseidon>::mds_layer(&state);
+        <F as Poseidon>::constant_layer(&mut state, 1);
+        for (i, &sbox_in) in state.iter().enumerate() {
+            assert_eq!(witness.get_wire(wire(WIRE_FULL_SBOX_0 + i)), sbox_in);
+        }
+
+        // The first partial round S-box input is the same in the fast partial rounds.
+        let mut state = swapped_inputs;
+        let mut round_ctr = 0;
+        <F as Poseidon>::full_rounds(&mut state, &mut round_ctr);
+        let first_round_constant =
+            F::from_canonical_u64(<F as Poseidon>::FAST_PARTIAL_FIRST_ROUND_CONSTANT[0]);
+        assert_eq!(
+            witness.get_wire(wire(WIRE_PARTIAL_SBOX)),
+            state[0] + first_round_constant
+        );
+
+        <F as Poseidon>::partial_rounds(&mut state, &mut round_ctr);
+        <F as Poseidon>::constant_layer(&mut state, round_ctr);
+        for (i, &sbox_in) in state.iter().enumerate() {
+            assert_eq!(witness.get_wire(wire(WIRE_FULL_SBOX_1 + i)), sbox_in);
+        }
+    }
+}
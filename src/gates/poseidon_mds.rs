@@ -0,0 +1,111 @@
+use plonky2::field::extension::Extendable;
+use plonky2::gates::gate::GateRef;
+use plonky2::gates::poseidon_mds::PoseidonMdsGate;
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::hashing::SPONGE_WIDTH;
+use plonky2::hash::poseidon::Poseidon;
+
+use crate::gates::{u64_array_literal, SolidityGate};
+
+const POSEIDON_MDS_GATE_TEMPLATE: &str = "library PoseidonMdsGateLib {
+    using GoldilocksFieldLib for uint64;
+    using GoldilocksExtLib for uint64[2];
+
+    uint32 constant SPONGE_WIDTH = $SPONGE_WIDTH;
+    uint32 constant D = $D;
+
+    uint32 constant WIRE_INPUT = $WIRE_INPUT;
+    uint32 constant WIRE_OUTPUT = $WIRE_OUTPUT;
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint64[SPONGE_WIDTH] memory circ = $MDS_MATRIX_CIRC;
+        uint64[SPONGE_WIDTH] memory diag = $MDS_MATRIX_DIAG;
+        // The MDS layer only scales by base field elements, so each of the D limbs of the
+        // extension algebra inputs can be processed independently.
+        for (uint32 r = 0; r < SPONGE_WIDTH; r++) {
+            for (uint32 j = 0; j < D; j++) {
+                uint64[2] memory computed_out;
+                for (uint32 i = 0; i < SPONGE_WIDTH; i++) {
+                    computed_out = computed_out.add(ev.wires[WIRE_INPUT + ((i + r) % SPONGE_WIDTH) * D + j].scalar_mul(circ[i]));
+                }
+                computed_out = computed_out.add(ev.wires[WIRE_INPUT + r * D + j].scalar_mul(diag[r]));
+                GatesUtilsLib.push(constraints, ev.filter, r * D + j, ev.wires[WIRE_OUTPUT + r * D + j].sub(computed_out));
+            }
+        }
+    }
+}
+";
+
+/// Solidity evaluation of `PoseidonMdsGate` constraints, mirroring `PoseidonMdsGate::eval_unfiltered`.
+pub fn poseidon_mds_gate_solidity_code<F: RichField + Extendable<D>, const D: usize>() -> String {
+    let mut code_str = POSEIDON_MDS_GATE_TEMPLATE.to_owned();
+    code_str = code_str.replace("$SPONGE_WIDTH", &*SPONGE_WIDTH.to_string());
+    code_str = code_str.replace("$D", &*D.to_string());
+    code_str = code_str.replace(
+        "$WIRE_INPUT",
+        &*PoseidonMdsGate::<F, D>::wires_input(0).start.to_string(),
+    );
+    code_str = code_str.replace(
+        "$WIRE_OUTPUT",
+        &*PoseidonMdsGate::<F, D>::wires_output(0).start.to_string(),
+    );
+    code_str = code_str.replace(
+        "$MDS_MATRIX_CIRC",
+        &*u64_array_literal(&<F as Poseidon>::MDS_MATRIX_CIRC),
+    );
+    code_str = code_str.replace(
+        "$MDS_MATRIX_DIAG",
+        &*u64_array_literal(&<F as Poseidon>::MDS_MATRIX_DIAG),
+    );
+    code_str
+}
+
+pub struct PoseidonMdsSolidityGate;
+
+impl<F: RichField + Extendable<D>, const D: usize> SolidityGate<F, D> for PoseidonMdsSolidityGate {
+    fn export_solidity_verification_code(&self, _: &GateRef<F, D>) -> String {
+        poseidon_mds_gate_solidity_code::<F, D>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::gates::gate::Gate;
+    use plonky2::gates::poseidon_mds::PoseidonMdsGate;
+    use plonky2::hash::hashing::SPONGE_WIDTH;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use crate::gates::poseidon_mds::poseidon_mds_gate_solidity_code;
+
+    #[test]
+    fn test_poseidon_mds_gate_solidity_code() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let code_str = poseidon_mds_gate_solidity_code::<F, D>();
+        assert!(code_str.starts_with("library PoseidonMdsGateLib {"));
+
+        // Only the placeholders filled by `generate_solidity_verifier` are left.
+        let leftover = code_str
+            .replace("$SET_FILTER", "")
+            .replace("$NUM_GATE_CONSTRAINTS", "");
+        assert!(!leftover.contains('$'));
+        assert!(code_str.contains(&format!(
+            "WIRE_INPUT = {};",
+            PoseidonMdsGate::<F, D>::wires_input(0).start
+        )));
+        assert!(code_str.contains(&format!(
+            "WIRE_OUTPUT = {};",
+            PoseidonMdsGate::<F, D>::wires_output(0).start
+        )));
+
+        // The Solidity code pushes one constraint per output limb.
+        let gate = PoseidonMdsGate::<F, D>::new();
+        assert_eq!(gate.num_constraints(), SPONGE_WIDTH * D);
+    }
+}
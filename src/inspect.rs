@@ -0,0 +1,386 @@
+use serde::Serialize;
+
+use crate::calldata::encode_hex_0x;
+use crate::error::GeneratorError;
+use crate::layout::ProofLayout;
+use crate::verifier::VerifierConfig;
+
+/// A value read from the proof bytes.
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum ProofElement {
+    /// A Merkle cap element or sibling, `0x` prefixed hex.
+    Hash(String),
+    /// A field element, or the length byte of a Merkle proof.
+    Field(u64),
+    /// An extension field element.
+    Ext([u64; 2]),
+}
+
+/// A section of the proof bytes and the values read from it.
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct ProofSection {
+    pub name: String,
+    /// Offset of the section in the proof bytes.
+    pub offset: usize,
+    pub size: usize,
+    pub elements: Vec<ProofElement>,
+}
+
+/// The sections of proof bytes, which may not have the size expected by the config.
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct ProofInspection {
+    /// Size of the proofs described by the config.
+    pub expected_size: usize,
+    pub size: usize,
+    /// The sections that fit in the proof bytes, in order.
+    pub sections: Vec<ProofSection>,
+    /// The first section that does not fit in the proof bytes, if they are too short.
+    pub truncated_section: Option<String>,
+    /// The bytes after the last section, `0x` prefixed hex, if the proof bytes are too long.
+    pub trailing_bytes: Option<String>,
+}
+
+struct Sections<'a> {
+    proof: &'a [u8],
+    layout: &'a ProofLayout,
+    sections: Vec<ProofSection>,
+    truncated_section: Option<String>,
+}
+
+impl<'a> Sections<'a> {
+    fn read_u64(&self, ptr: usize) -> u64 {
+        u64::from_le_bytes(self.proof[ptr..ptr + 8].try_into().unwrap())
+    }
+
+    /// Whether the section fits in the proof bytes. Records the first one which does not, after
+    /// which no section is read.
+    fn fits(&mut self, name: &str, offset: usize, size: usize) -> bool {
+        if self.truncated_section.is_some() {
+            return false;
+        }
+        if offset + size > self.proof.len() {
+            self.truncated_section = Some(name.to_owned());
+            return false;
+        }
+        true
+    }
+
+    fn push(&mut self, name: String, offset: usize, size: usize, elements: Vec<ProofElement>) {
+        self.sections.push(ProofSection {
+            name,
+            offset,
+            size,
+            elements,
+        });
+    }
+
+    fn hashes(&mut self, name: String, offset: usize, len: usize) {
+        let size = self.layout.hash_size;
+        if !self.fits(&name, offset, len * size) {
+            return;
+        }
+        let elements = (0..len)
+            .map(|i| {
+                let ptr = offset + i * size;
+                ProofElement::Hash(encode_hex_0x(&self.proof[ptr..ptr + size]))
+            })
+            .collect();
+        self.push(name, offset, len * size, elements);
+    }
+
+    fn fields(&mut self, name: String, offset: usize, len: usize) {
+        let size = self.layout.field_size;
+        if !self.fits(&name, offset, len * size) {
+            return;
+        }
+        let elements = (0..len)
+            .map(|i| ProofElement::Field(self.read_u64(offset + i * size)))
+            .collect();
+        self.push(name, offset, len * size, elements);
+    }
+
+    fn exts(&mut self, name: String, offset: usize, len: usize) {
+        let size = self.layout.ext_field_size;
+        if !self.fits(&name, offset, len * size) {
+            return;
+        }
+        let elements = (0..len)
+            .map(|i| {
+                let ptr = offset + i * size;
+                ProofElement::Ext([self.read_u64(ptr), self.read_u64(ptr + 8)])
+            })
+            .collect();
+        self.push(name, offset, len * size, elements);
+    }
+
+    /// The length byte of a Merkle proof, followed by its siblings.
+    fn merkle_proof(&mut self, name: &str, p_ptr: usize, len: usize) {
+        let len_name = name.to_owned() + "_len";
+        if self.fits(&len_name, p_ptr - 1, 1) {
+            let height = ProofElement::Field(self.proof[p_ptr - 1] as u64);
+            self.push(len_name, p_ptr - 1, 1, vec![height]);
+        }
+        self.hashes(name.to_owned() + "_p", p_ptr, len);
+    }
+}
+
+/// Splits `proof` into the sections read by the generated `ProofLib`, in order. Proof bytes of the
+/// wrong size are inspected as well, up to the first section that does not fit.
+pub fn inspect_proof(
+    proof: &[u8],
+    conf: &VerifierConfig,
+) -> Result<ProofInspection, GeneratorError> {
+    // The sizes are read from `conf.json`, the layout is only valid with the supported ones.
+    conf.check()?;
+    let layout = ProofLayout::new(conf);
+
+    let mut s = Sections {
+        proof,
+        layout: &layout,
+        sections: Vec::new(),
+        truncated_section: None,
+    };
+    s.hashes(
+        "wires_cap".to_owned(),
+        layout.wires_cap_ptr,
+        conf.num_wires_cap,
+    );
+    s.hashes(
+        "plonk_zs_partial_products_cap".to_owned(),
+        layout.plonk_zs_partial_products_cap_ptr,
+        conf.num_plonk_zs_partial_products_cap,
+    );
+    s.hashes(
+        "quotient_polys_cap".to_owned(),
+        layout.quotient_polys_cap_ptr,
+        conf.num_quotient_polys_cap,
+    );
+
+    for (name, ptr, len) in [
+        (
+            "openings_constants",
+            layout.openings_constants_ptr,
+            conf.num_openings_constants,
+        ),
+        (
+            "openings_plonk_sigmas",
+            layout.openings_plonk_sigmas_ptr,
+            conf.num_openings_plonk_sigmas,
+        ),
+        (
+            "openings_wires",
+            layout.openings_wires_ptr,
+            conf.num_openings_wires,
+        ),
+        (
+            "openings_plonk_zs",
+            layout.openings_plonk_zs_ptr,
+            conf.num_openings_plonk_zs,
+        ),
+        (
+            "openings_plonk_zs_next",
+            layout.openings_plonk_zs_next_ptr,
+            conf.num_openings_plonk_zs_next,
+        ),
+        (
+            "openings_partial_products",
+            layout.openings_partial_products_ptr,
+            conf.num_openings_partial_products,
+        ),
+        (
+            "openings_quotient_polys",
+            layout.openings_quotient_polys_ptr,
+            conf.num_openings_quotient_polys,
+        ),
+    ] {
+        s.exts(name.to_owned(), ptr, len);
+    }
+
+    for i in 0..conf.num_fri_commit_round {
+        s.hashes(
+            format!("fri_commit_phase_merkle_caps[{}]", i),
+            layout.fri_commit_phase_merkle_caps_ptr + i * layout.fri_commit_round_size,
+            conf.fri_commit_merkle_cap_height,
+        );
+    }
+
+    for r in 0..conf.num_fri_query_round {
+        let round_ptr = layout.fri_query_round(r);
+        for (tree, v_ptr, p_ptr, v_len, p_len) in [
+            (
+                "init_constants_sigmas",
+                layout.init_constants_sigmas_v_ptr,
+                layout.init_constants_sigmas_p_ptr,
+                conf.num_fri_query_init_constants_sigmas_v,
+                conf.num_fri_query_init_constants_sigmas_p,
+            ),
+            (
+                "init_wires",
+                layout.init_wires_v_ptr,
+                layout.init_wires_p_ptr,
+                conf.num_fri_query_init_wires_v,
+                conf.num_fri_query_init_wires_p,
+            ),
+            (
+                "init_zs_partial",
+                layout.init_zs_partial_v_ptr,
+                layout.init_zs_partial_p_ptr,
+                conf.num_fri_query_init_zs_partial_v,
+                conf.num_fri_query_init_zs_partial_p,
+            ),
+            (
+                "init_quotient",
+                layout.init_quotient_v_ptr,
+                layout.init_quotient_p_ptr,
+                conf.num_fri_query_init_quotient_v,
+                conf.num_fri_query_init_quotient_p,
+            ),
+        ] {
+            let name = format!("fri_query_round[{}].{}", r, tree);
+            s.fields(name.clone() + "_v", round_ptr + v_ptr, v_len);
+            s.merkle_proof(&name, round_ptr + p_ptr, p_len);
+        }
+        for (i, (v_len, p_len)) in conf
+            .num_fri_query_step_v
+            .iter()
+            .zip(&conf.num_fri_query_step_p)
+            .enumerate()
+        {
+            let name = format!("fri_query_round[{}].step[{}]", r, i);
+            s.exts(
+                name.clone() + "_v",
+                round_ptr + layout.fri_query_step_v_ptrs[i],
+                *v_len,
+            );
+            s.merkle_proof(&name, round_ptr + layout.fri_query_step_p_ptrs[i], *p_len);
+        }
+    }
+
+    s.exts(
+        "fri_final_poly_ext_v".to_owned(),
+        layout.fri_final_poly_ext_v_ptr,
+        conf.num_fri_final_poly_ext_v,
+    );
+    s.fields("fri_pow_witness".to_owned(), layout.fri_pow_witness_ptr, 1);
+    s.fields(
+        "public_inputs".to_owned(),
+        layout.public_inputs_ptr,
+        conf.num_public_inputs,
+    );
+    Ok(ProofInspection {
+        expected_size: layout.proof_size,
+        size: proof.len(),
+        sections: s.sections,
+        truncated_section: s.truncated_section,
+        trailing_bytes: proof
+            .get(layout.proof_size..)
+            .filter(|bytes| !bytes.is_empty())
+            .map(encode_hex_0x),
+    })
+}
+
+/// Same as `inspect_proof`, as pretty-printed JSON.
+pub fn inspect_proof_json(proof: &[u8], conf: &VerifierConfig) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(&inspect_proof(proof, conf)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::types::PrimeField64;
+    use plonky2::plonk::config::{GenericConfig, GenericHashOut};
+
+    use crate::calldata::encode_hex_0x;
+    use crate::config::KeccakGoldilocksConfig2;
+    use crate::inspect::{inspect_proof, inspect_proof_json, ProofElement};
+    use crate::test_utils::dummy_proof;
+    use crate::verifier::generate_verifier_config;
+    use crate::wrap::final_config;
+
+    #[test]
+    fn test_inspect_proof() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+
+        let (proof, _, _) = dummy_proof::<F, KC2, D>(&final_config(), 4_000, 2)?;
+        let conf = generate_verifier_config(&proof)?;
+        let bytes = proof.to_bytes()?;
+
+        let inspection = inspect_proof(&bytes, &conf)?;
+        assert_eq!(inspection.size, inspection.expected_size);
+        assert_eq!(inspection.truncated_section, None);
+        assert_eq!(inspection.trailing_bytes, None);
+        let sections = inspection.sections;
+        // The sections cover the whole proof, in order.
+        let mut offset = 0;
+        for section in &sections {
+            assert_eq!(section.offset, offset, "{}", section.name);
+            offset += section.size;
+        }
+        assert_eq!(offset, bytes.len());
+
+        let section = |name: &str| sections.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(
+            section("wires_cap").elements,
+            [ProofElement::Hash(encode_hex_0x(
+                &proof.proof.wires_cap.0[0].to_bytes()
+            ))]
+        );
+        let zeta_wire = proof.proof.openings.wires[0].0;
+        assert_eq!(
+            section("openings_wires").elements[0],
+            ProofElement::Ext([
+                zeta_wire[0].to_canonical_u64(),
+                zeta_wire[1].to_canonical_u64()
+            ])
+        );
+        let round = &proof.proof.opening_proof.query_round_proofs[1];
+        assert_eq!(
+            section("fri_query_round[1].step[0]_len").elements,
+            [ProofElement::Field(
+                round.steps[0].merkle_proof.siblings.len() as u64
+            )]
+        );
+        assert_eq!(
+            section("fri_pow_witness").elements,
+            [ProofElement::Field(
+                proof.proof.opening_proof.pow_witness.to_canonical_u64()
+            )]
+        );
+        assert_eq!(
+            section("public_inputs").elements,
+            [ProofElement::Field(0), ProofElement::Field(1)]
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&inspect_proof_json(&bytes, &conf)?)?;
+        assert_eq!(json["sections"][sections.len() - 1]["elements"][1], 1);
+
+        // A truncated proof is read up to the first section that does not fit.
+        let truncated = inspect_proof(&bytes[..bytes.len() - 1], &conf)?;
+        assert_eq!(
+            truncated.truncated_section.as_deref(),
+            Some("public_inputs")
+        );
+        assert_eq!(truncated.sections[..], sections[..sections.len() - 1]);
+        let truncated = inspect_proof(&bytes[..30], &conf)?;
+        assert_eq!(truncated.sections.len(), 1);
+        assert_eq!(
+            truncated.truncated_section.as_deref(),
+            Some("plonk_zs_partial_products_cap")
+        );
+
+        let mut extended = bytes.clone();
+        extended.extend_from_slice(&[1, 2]);
+        let extended = inspect_proof(&extended, &conf)?;
+        assert_eq!(extended.sections, sections);
+        assert_eq!(extended.trailing_bytes.as_deref(), Some("0x0102"));
+
+        // Configs with unsupported sizes are rejected instead of misreading the proof.
+        let mut invalid = conf.clone();
+        invalid.merkle_height_size = 0;
+        assert!(inspect_proof(&bytes, &invalid).is_err());
+        Ok(())
+    }
+}
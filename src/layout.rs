@@ -0,0 +1,223 @@
+use serde::Serialize;
+
+use crate::verifier::VerifierConfig;
+
+/// Byte offsets of every section of the proofs described by a `VerifierConfig`, as read by the
+/// generated `ProofLib`. The `*_ptr` fields are absolute offsets in the proof, except the ones of a
+/// FRI query round which are relative to the start of the round.
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct ProofLayout {
+    pub hash_size: usize,
+    pub field_size: usize,
+    pub ext_field_size: usize,
+
+    pub wires_cap_ptr: usize,
+    pub plonk_zs_partial_products_cap_ptr: usize,
+    pub quotient_polys_cap_ptr: usize,
+
+    // openings
+    pub openings_constants_ptr: usize,
+    pub openings_plonk_sigmas_ptr: usize,
+    pub openings_wires_ptr: usize,
+    pub openings_plonk_zs_ptr: usize,
+    pub openings_plonk_zs_next_ptr: usize,
+    pub openings_partial_products_ptr: usize,
+    pub openings_quotient_polys_ptr: usize,
+
+    // fri proof
+    // .commit phase
+    pub fri_commit_phase_merkle_caps_ptr: usize,
+    pub fri_commit_round_size: usize,
+    // .query round, each Merkle proof is preceded by its length in one byte
+    pub fri_query_round_ptr: usize,
+    pub fri_query_round_size: usize,
+    // ..init
+    pub init_constants_sigmas_v_ptr: usize,
+    pub init_constants_sigmas_p_ptr: usize,
+    pub init_wires_v_ptr: usize,
+    pub init_wires_p_ptr: usize,
+    pub init_zs_partial_v_ptr: usize,
+    pub init_zs_partial_p_ptr: usize,
+    pub init_quotient_v_ptr: usize,
+    pub init_quotient_p_ptr: usize,
+    // ..steps
+    pub fri_query_step_v_ptrs: Vec<usize>,
+    pub fri_query_step_p_ptrs: Vec<usize>,
+    // .final poly
+    pub fri_final_poly_ext_v_ptr: usize,
+    pub fri_pow_witness_ptr: usize,
+
+    // public inputs
+    pub public_inputs_ptr: usize,
+    /// Size in bytes of the proof, including its public inputs.
+    pub proof_size: usize,
+}
+
+impl ProofLayout {
+    pub fn new(conf: &VerifierConfig) -> Self {
+        let wires_cap_ptr = 0;
+        let plonk_zs_partial_products_cap_ptr = wires_cap_ptr + conf.num_wires_cap * conf.hash_size;
+        let quotient_polys_cap_ptr = plonk_zs_partial_products_cap_ptr
+            + conf.num_plonk_zs_partial_products_cap * conf.hash_size;
+
+        let openings_constants_ptr =
+            quotient_polys_cap_ptr + conf.num_quotient_polys_cap * conf.hash_size;
+        let openings_plonk_sigmas_ptr =
+            openings_constants_ptr + conf.num_openings_constants * conf.ext_field_size;
+        let openings_wires_ptr =
+            openings_plonk_sigmas_ptr + conf.num_openings_plonk_sigmas * conf.ext_field_size;
+        let openings_plonk_zs_ptr =
+            openings_wires_ptr + conf.num_openings_wires * conf.ext_field_size;
+        let openings_plonk_zs_next_ptr =
+            openings_plonk_zs_ptr + conf.num_openings_plonk_zs * conf.ext_field_size;
+        let openings_partial_products_ptr =
+            openings_plonk_zs_next_ptr + conf.num_openings_plonk_zs_next * conf.ext_field_size;
+        let openings_quotient_polys_ptr = openings_partial_products_ptr
+            + conf.num_openings_partial_products * conf.ext_field_size;
+
+        let fri_commit_phase_merkle_caps_ptr =
+            openings_quotient_polys_ptr + conf.num_openings_quotient_polys * conf.ext_field_size;
+        let fri_commit_round_size = conf.fri_commit_merkle_cap_height * conf.hash_size;
+        let fri_query_round_ptr =
+            fri_commit_phase_merkle_caps_ptr + conf.num_fri_commit_round * fri_commit_round_size;
+
+        let init_constants_sigmas_v_ptr = 0;
+        let init_constants_sigmas_p_ptr = init_constants_sigmas_v_ptr
+            + conf.num_fri_query_init_constants_sigmas_v * conf.field_size
+            + conf.merkle_height_size;
+        let init_wires_v_ptr = init_constants_sigmas_p_ptr
+            + conf.num_fri_query_init_constants_sigmas_p * conf.hash_size;
+        let init_wires_p_ptr = init_wires_v_ptr
+            + conf.num_fri_query_init_wires_v * conf.field_size
+            + conf.merkle_height_size;
+        let init_zs_partial_v_ptr =
+            init_wires_p_ptr + conf.num_fri_query_init_wires_p * conf.hash_size;
+        let init_zs_partial_p_ptr = init_zs_partial_v_ptr
+            + conf.num_fri_query_init_zs_partial_v * conf.field_size
+            + conf.merkle_height_size;
+        let init_quotient_v_ptr =
+            init_zs_partial_p_ptr + conf.num_fri_query_init_zs_partial_p * conf.hash_size;
+        let init_quotient_p_ptr = init_quotient_v_ptr
+            + conf.num_fri_query_init_quotient_v * conf.field_size
+            + conf.merkle_height_size;
+
+        let mut round_ptr =
+            init_quotient_p_ptr + conf.num_fri_query_init_quotient_p * conf.hash_size;
+        let mut fri_query_step_v_ptrs = Vec::with_capacity(conf.num_fri_query_step_v.len());
+        let mut fri_query_step_p_ptrs = Vec::with_capacity(conf.num_fri_query_step_p.len());
+        for (step_v, step_p) in conf
+            .num_fri_query_step_v
+            .iter()
+            .zip(&conf.num_fri_query_step_p)
+        {
+            fri_query_step_v_ptrs.push(round_ptr);
+            round_ptr += step_v * conf.ext_field_size + conf.merkle_height_size;
+            fri_query_step_p_ptrs.push(round_ptr);
+            round_ptr += step_p * conf.hash_size;
+        }
+        let fri_query_round_size = round_ptr;
+
+        let fri_final_poly_ext_v_ptr =
+            fri_query_round_ptr + conf.num_fri_query_round * fri_query_round_size;
+        let fri_pow_witness_ptr =
+            fri_final_poly_ext_v_ptr + conf.num_fri_final_poly_ext_v * conf.ext_field_size;
+        let public_inputs_ptr = fri_pow_witness_ptr + conf.field_size;
+        let proof_size = public_inputs_ptr + conf.num_public_inputs * conf.field_size;
+
+        Self {
+            hash_size: conf.hash_size,
+            field_size: conf.field_size,
+            ext_field_size: conf.ext_field_size,
+            wires_cap_ptr,
+            plonk_zs_partial_products_cap_ptr,
+            quotient_polys_cap_ptr,
+            openings_constants_ptr,
+            openings_plonk_sigmas_ptr,
+            openings_wires_ptr,
+            openings_plonk_zs_ptr,
+            openings_plonk_zs_next_ptr,
+            openings_partial_products_ptr,
+            openings_quotient_polys_ptr,
+            fri_commit_phase_merkle_caps_ptr,
+            fri_commit_round_size,
+            fri_query_round_ptr,
+            fri_query_round_size,
+            init_constants_sigmas_v_ptr,
+            init_constants_sigmas_p_ptr,
+            init_wires_v_ptr,
+            init_wires_p_ptr,
+            init_zs_partial_v_ptr,
+            init_zs_partial_p_ptr,
+            init_quotient_v_ptr,
+            init_quotient_p_ptr,
+            fri_query_step_v_ptrs,
+            fri_query_step_p_ptrs,
+            fri_final_poly_ext_v_ptr,
+            fri_pow_witness_ptr,
+            public_inputs_ptr,
+            proof_size,
+        }
+    }
+
+    /// Absolute offset of the query round `r`.
+    pub fn fri_query_round(&self, r: usize) -> usize {
+        self.fri_query_round_ptr + self.fri_query_round_size * r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::types::PrimeField64;
+    use plonky2::plonk::config::{GenericConfig, GenericHashOut};
+
+    use crate::config::KeccakGoldilocksConfig2;
+    use crate::layout::ProofLayout;
+    use crate::test_utils::dummy_proof;
+    use crate::verifier::generate_verifier_config;
+    use crate::wrap::final_config;
+
+    #[test]
+    fn test_proof_layout() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+
+        let (proof, _, _) = dummy_proof::<F, KC2, D>(&final_config(), 4_000, 2)?;
+        let conf = generate_verifier_config(&proof)?;
+        let layout = ProofLayout::new(&conf);
+        let bytes = proof.to_bytes()?;
+        assert_eq!(layout.proof_size, bytes.len());
+
+        let read_u64 = |ptr: usize| u64::from_le_bytes(bytes[ptr..ptr + 8].try_into().unwrap());
+        let quotient_cap = proof.proof.quotient_polys_cap.0[0].to_bytes();
+        assert_eq!(
+            &bytes[layout.quotient_polys_cap_ptr..layout.quotient_polys_cap_ptr + conf.hash_size],
+            &quotient_cap[..]
+        );
+        assert_eq!(
+            read_u64(layout.openings_plonk_zs_ptr),
+            proof.proof.openings.plonk_zs[0].0[0].to_canonical_u64()
+        );
+        let fri_proof = &proof.proof.opening_proof;
+        let round = &fri_proof.query_round_proofs[1];
+        assert_eq!(
+            read_u64(layout.fri_query_round(1) + layout.init_wires_v_ptr),
+            round.initial_trees_proof.evals_proofs[1].0[0].to_canonical_u64()
+        );
+        let step_p_ptr = layout.fri_query_round(1) + layout.fri_query_step_p_ptrs[0];
+        assert_eq!(
+            bytes[step_p_ptr - 1] as usize,
+            round.steps[0].merkle_proof.siblings.len()
+        );
+        assert_eq!(
+            read_u64(layout.fri_pow_witness_ptr),
+            fri_proof.pow_witness.to_canonical_u64()
+        );
+        assert_eq!(read_u64(layout.public_inputs_ptr + conf.field_size), 1);
+
+        let json = serde_json::to_value(&layout)?;
+        assert_eq!(json["proof_size"], bytes.len());
+        Ok(())
+    }
+}
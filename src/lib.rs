@@ -2,5 +2,23 @@
 #![feature(generic_const_exprs)]
 extern crate core;
 
+pub mod calldata;
+pub mod circuit_data;
+pub mod compatibility;
 pub mod config;
+pub mod error;
+#[cfg(feature = "evm")]
+pub mod evm;
+pub mod gas;
+pub mod gates;
+pub mod inspect;
+pub mod layout;
+pub mod project;
+pub mod reference;
+pub mod solidity;
+pub mod templates;
+#[cfg(test)]
+mod test_utils;
+pub mod tuner;
 pub mod verifier;
+pub mod wrap;
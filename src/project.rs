@@ -0,0 +1,154 @@
+use std::path::Path;
+
+use anyhow::Result;
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
+use plonky2::plonk::config::GenericConfig;
+use plonky2::plonk::proof::ProofWithPublicInputs;
+
+use crate::verifier::{generate_proof_base64, generate_solidity_verifier, VerifierConfig};
+
+/// Libraries the generated contracts import, besides `GatesLib.sol` and `ProofLib.sol`.
+pub const STATIC_CONTRACTS: [(&str, &str); 4] = [
+    (
+        "Challenger.sol",
+        include_str!("../contract/contracts/Challenger.sol"),
+    ),
+    ("Plonk.sol", include_str!("../contract/contracts/Plonk.sol")),
+    (
+        "GoldilocksField.sol",
+        include_str!("../contract/contracts/GoldilocksField.sol"),
+    ),
+    (
+        "GoldilocksExt.sol",
+        include_str!("../contract/contracts/GoldilocksExt.sol"),
+    ),
+];
+
+/// The generated contracts of a verifier, with a proof and config to test them with.
+#[derive(Debug, Clone)]
+pub struct VerifierProject {
+    pub conf: VerifierConfig,
+    pub contract: String,
+    pub gates_lib: String,
+    pub proof_lib: String,
+    pub proof_base64: String,
+}
+
+impl VerifierProject {
+    pub fn generate<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+        conf: &VerifierConfig,
+        common: &CommonCircuitData<F, C, D>,
+        verifier_only: &VerifierOnlyCircuitData<C, D>,
+        proof: &ProofWithPublicInputs<F, C, D>,
+    ) -> Result<Self> {
+        let (contract, gates_lib, proof_lib) =
+            generate_solidity_verifier(conf, common, verifier_only)?;
+        let proof_base64 = generate_proof_base64(proof, conf)?;
+        Ok(Self {
+            conf: conf.clone(),
+            contract,
+            gates_lib,
+            proof_lib,
+            proof_base64,
+        })
+    }
+
+    /// Writes the contracts and static libraries to `out_dir/contracts`, and the proof and config
+    /// fixtures read by the hardhat tests to `out_dir/test/data`, as laid out in `./contract`.
+    pub fn write<P: AsRef<Path>>(&self, out_dir: P) -> Result<()> {
+        write_contracts(&out_dir, &self.contract, &self.gates_lib, &self.proof_lib)?;
+
+        let data_dir = out_dir.as_ref().join("test/data");
+        std::fs::create_dir_all(&data_dir)?;
+        let proof_json = serde_json::to_string(&[&self.proof_base64])?;
+        std::fs::write(data_dir.join("proof.json"), proof_json)?;
+        std::fs::write(
+            data_dir.join("conf.json"),
+            serde_json::to_string(&self.conf)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// Writes the generated contracts and the static libraries they import to `out_dir/contracts`.
+pub fn write_contracts<P: AsRef<Path>>(
+    out_dir: P,
+    contract: &str,
+    gates_lib: &str,
+    proof_lib: &str,
+) -> Result<()> {
+    let contracts_dir = out_dir.as_ref().join("contracts");
+    std::fs::create_dir_all(&contracts_dir)?;
+    for (file_name, code) in STATIC_CONTRACTS {
+        std::fs::write(contracts_dir.join(file_name), code)?;
+    }
+    std::fs::write(contracts_dir.join("Verifier.sol"), contract)?;
+    std::fs::write(contracts_dir.join("GatesLib.sol"), gates_lib)?;
+    std::fs::write(contracts_dir.join("ProofLib.sol"), proof_lib)?;
+    Ok(())
+}
+
+/// Generates the verifier of `proof` and writes it to `out_dir`, see `VerifierProject::write`.
+pub fn write_verifier_project<
+    P: AsRef<Path>,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    out_dir: P,
+    conf: &VerifierConfig,
+    common: &CommonCircuitData<F, C, D>,
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+    proof: &ProofWithPublicInputs<F, C, D>,
+) -> Result<()> {
+    VerifierProject::generate(conf, common, verifier_only, proof)?.write(out_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::plonk::config::GenericConfig;
+
+    use crate::config::KeccakGoldilocksConfig2;
+    use crate::project::{write_verifier_project, STATIC_CONTRACTS};
+    use crate::test_utils::dummy_proof;
+    use crate::verifier::generate_verifier_config;
+    use crate::wrap::final_config;
+
+    #[test]
+    fn test_write_verifier_project() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+
+        let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config(), 4_000, 1)?;
+        let conf = generate_verifier_config(&proof)?;
+
+        let out_dir = std::env::temp_dir().join("plonky2_solidity_project");
+        write_verifier_project(&out_dir, &conf, &cd, &vd, &proof)?;
+
+        let contracts_dir = out_dir.join("contracts");
+        for file_name in STATIC_CONTRACTS
+            .iter()
+            .map(|(file_name, _)| *file_name)
+            .chain(["Verifier.sol", "GatesLib.sol", "ProofLib.sol"])
+        {
+            // The package compiles outside of a hardhat project.
+            let code = std::fs::read_to_string(contracts_dir.join(file_name))?;
+            assert!(!code.contains("import \"hardhat/"), "{}", file_name);
+        }
+        let proof_json: Vec<String> = serde_json::from_str(&std::fs::read_to_string(
+            out_dir.join("test/data/proof.json"),
+        )?)?;
+        assert_eq!(base64::decode(&proof_json[0])?, proof.to_bytes()?);
+        let conf_json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+            out_dir.join("test/data/conf.json"),
+        )?)?;
+        assert_eq!(conf_json, serde_json::to_value(&conf)?);
+
+        std::fs::remove_dir_all(&out_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,708 @@
+use std::marker::PhantomData;
+
+use anyhow::{ensure, Result};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::{HashOut, RichField};
+use plonky2::iop::challenger::Challenger;
+use plonky2::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
+use plonky2::plonk::config::{GenericConfig, GenericHashOut, Hasher};
+use plonky2::plonk::vars::EvaluationVars;
+use plonky2::util::reverse_bits;
+use plonky2_util::log2_strict;
+
+use crate::config::sha256;
+use crate::error::GeneratorError;
+use crate::layout::ProofLayout;
+use crate::verifier::{proof_size, VerifierConfig};
+
+/// Reads the flat proof bytes at the offsets the generated `ProofLib` uses.
+struct FlatProof<'a, F: RichField + Extendable<D>, const D: usize> {
+    conf: &'a VerifierConfig,
+    layout: ProofLayout,
+    bytes: &'a [u8],
+    _phantom: PhantomData<F>,
+}
+
+impl<'a, F: RichField + Extendable<D>, const D: usize> FlatProof<'a, F, D> {
+    fn new(conf: &'a VerifierConfig, bytes: &'a [u8]) -> Self {
+        Self {
+            conf,
+            layout: ProofLayout::new(conf),
+            bytes,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn hash(&self, ptr: usize) -> &'a [u8] {
+        &self.bytes[ptr..ptr + self.conf.hash_size]
+    }
+
+    fn u64(&self, ptr: usize) -> u64 {
+        u64::from_le_bytes(self.bytes[ptr..ptr + 8].try_into().unwrap())
+    }
+
+    fn elements(&self, ptr: usize, n: usize) -> Vec<F> {
+        (0..n)
+            .map(|i| F::from_noncanonical_u64(self.u64(ptr + i * self.conf.field_size)))
+            .collect()
+    }
+
+    fn extensions(&self, ptr: usize, n: usize) -> Vec<F::Extension> {
+        (0..n)
+            .map(|i| {
+                let ptr = ptr + i * self.conf.ext_field_size;
+                F::Extension::from_basefield_array(std::array::from_fn(|j| {
+                    F::from_noncanonical_u64(self.u64(ptr + j * self.conf.field_size))
+                }))
+            })
+            .collect()
+    }
+
+    fn wires_cap(&self, i: usize) -> &'a [u8] {
+        self.hash(i * self.conf.hash_size)
+    }
+
+    fn plonk_zs_partial_products_cap(&self, i: usize) -> &'a [u8] {
+        self.hash(self.layout.plonk_zs_partial_products_cap_ptr + i * self.conf.hash_size)
+    }
+
+    fn quotient_polys_cap(&self, i: usize) -> &'a [u8] {
+        self.hash(self.layout.quotient_polys_cap_ptr + i * self.conf.hash_size)
+    }
+
+    fn openings_constants(&self) -> Vec<F::Extension> {
+        self.extensions(
+            self.layout.openings_constants_ptr,
+            self.conf.num_openings_constants,
+        )
+    }
+
+    fn openings_plonk_sigmas(&self) -> Vec<F::Extension> {
+        self.extensions(
+            self.layout.openings_plonk_sigmas_ptr,
+            self.conf.num_openings_plonk_sigmas,
+        )
+    }
+
+    fn openings_wires(&self) -> Vec<F::Extension> {
+        self.extensions(self.layout.openings_wires_ptr, self.conf.num_openings_wires)
+    }
+
+    fn openings_plonk_zs(&self) -> Vec<F::Extension> {
+        self.extensions(
+            self.layout.openings_plonk_zs_ptr,
+            self.conf.num_openings_plonk_zs,
+        )
+    }
+
+    fn openings_plonk_zs_next(&self) -> Vec<F::Extension> {
+        self.extensions(
+            self.layout.openings_plonk_zs_next_ptr,
+            self.conf.num_openings_plonk_zs_next,
+        )
+    }
+
+    fn openings_partial_products(&self) -> Vec<F::Extension> {
+        self.extensions(
+            self.layout.openings_partial_products_ptr,
+            self.conf.num_openings_partial_products,
+        )
+    }
+
+    fn openings_quotient_polys(&self) -> Vec<F::Extension> {
+        self.extensions(
+            self.layout.openings_quotient_polys_ptr,
+            self.conf.num_openings_quotient_polys,
+        )
+    }
+
+    fn fri_commit_phase_merkle_caps(&self, i: usize, j: usize) -> &'a [u8] {
+        self.hash(
+            self.layout.fri_commit_phase_merkle_caps_ptr
+                + i * self.layout.fri_commit_round_size
+                + j * self.conf.hash_size,
+        )
+    }
+
+    fn fri_query_init_constants_sigmas_v(&self, r: usize) -> Vec<F> {
+        self.elements(
+            self.layout.fri_query_round(r) + self.layout.init_constants_sigmas_v_ptr,
+            self.conf.num_fri_query_init_constants_sigmas_v,
+        )
+    }
+
+    fn fri_query_init_wires_v(&self, r: usize) -> Vec<F> {
+        self.elements(
+            self.layout.fri_query_round(r) + self.layout.init_wires_v_ptr,
+            self.conf.num_fri_query_init_wires_v,
+        )
+    }
+
+    fn fri_query_init_zs_partial_v(&self, r: usize) -> Vec<F> {
+        self.elements(
+            self.layout.fri_query_round(r) + self.layout.init_zs_partial_v_ptr,
+            self.conf.num_fri_query_init_zs_partial_v,
+        )
+    }
+
+    fn fri_query_init_quotient_v(&self, r: usize) -> Vec<F> {
+        self.elements(
+            self.layout.fri_query_round(r) + self.layout.init_quotient_v_ptr,
+            self.conf.num_fri_query_init_quotient_v,
+        )
+    }
+
+    fn fri_query_step_v(&self, r: usize, s: usize) -> Vec<F::Extension> {
+        self.extensions(
+            self.layout.fri_query_round(r) + self.layout.fri_query_step_v_ptrs[s],
+            self.conf.num_fri_query_step_v[s],
+        )
+    }
+
+    fn fri_final_poly_ext_v(&self) -> Vec<F::Extension> {
+        self.extensions(
+            self.layout.fri_final_poly_ext_v_ptr,
+            self.conf.num_fri_final_poly_ext_v,
+        )
+    }
+
+    fn fri_pow_witness(&self) -> &'a [u8] {
+        &self.bytes[self.layout.fri_pow_witness_ptr
+            ..self.layout.fri_pow_witness_ptr + self.conf.field_size]
+    }
+
+    fn public_input_hash(&self) -> HashOut<F> {
+        if self.conf.num_public_inputs == 0 {
+            return HashOut::from_vec(vec![F::ZERO; 4]);
+        }
+        let h = sha256(&self.bytes[self.layout.public_inputs_ptr..]).0;
+        HashOut::from_vec(
+            h.chunks(8)
+                .map(|chunk| {
+                    F::from_noncanonical_u64(u64::from_le_bytes(chunk.try_into().unwrap()))
+                })
+                .collect(),
+        )
+    }
+
+    /// Same as `get_fri_merkle_proof_to_cap` in `ProofLib`: the leaf values end one byte before
+    /// `p_ptr`. Returns the root of the path and the index of the cap element it must match.
+    fn merkle_proof_to_cap(
+        &self,
+        v_ptr: usize,
+        p_ptr: usize,
+        merkle_proof_len: usize,
+        mut leaf_index: usize,
+    ) -> ([u8; 32], usize) {
+        let hash_size = self.conf.hash_size;
+        let leaf = &self.bytes[v_ptr..p_ptr - self.conf.merkle_height_size];
+        let mut current_digest = [0u8; 32];
+        if leaf.len() <= hash_size {
+            current_digest[..leaf.len()].copy_from_slice(leaf);
+        } else {
+            current_digest = keccak_hash::keccak(leaf).0;
+        }
+
+        for i in 0..merkle_proof_len {
+            let sibling = self.hash(p_ptr + i * hash_size);
+            let bit = leaf_index & 1;
+            leaf_index >>= 1;
+            let mut input = Vec::with_capacity(2 * hash_size);
+            if bit == 1 {
+                input.extend_from_slice(sibling);
+                input.extend_from_slice(&current_digest[..hash_size]);
+            } else {
+                input.extend_from_slice(&current_digest[..hash_size]);
+                input.extend_from_slice(sibling);
+            }
+            current_digest = keccak_hash::keccak(input).0;
+        }
+
+        (current_digest, leaf_index)
+    }
+
+    fn verify_merkle_proof_to_cap(
+        &self,
+        v_ptr: usize,
+        p_ptr: usize,
+        merkle_proof_len: usize,
+        leaf_index: usize,
+        cap: impl Fn(usize) -> Vec<u8>,
+    ) -> bool {
+        let (root, cap_index) =
+            self.merkle_proof_to_cap(v_ptr, p_ptr, merkle_proof_len, leaf_index);
+        root[..self.conf.hash_size] == cap(cap_index)[..]
+    }
+}
+
+struct ProofChallenges<F: RichField + Extendable<D>, const D: usize> {
+    plonk_betas: Vec<F>,
+    plonk_gammas: Vec<F>,
+    plonk_alphas: Vec<F>,
+    plonk_zeta: F::Extension,
+    fri_alpha: F::Extension,
+    fri_betas: Vec<F::Extension>,
+    fri_pow_response: u64,
+    fri_query_indices: Vec<usize>,
+    public_input_hash: HashOut<F>,
+}
+
+fn get_challenges<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    proof: &FlatProof<F, D>,
+    common: &CommonCircuitData<F, C, D>,
+) -> ProofChallenges<F, D> {
+    let conf = proof.conf;
+    let num_challenges = common.config.num_challenges;
+    let hash =
+        |bytes: &[u8]| <<C::Hasher as Hasher<F>>::Hash as GenericHashOut<F>>::from_bytes(bytes);
+
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+    challenger.observe_hash::<C::Hasher>(common.circuit_digest);
+    let public_input_hash = proof.public_input_hash();
+    challenger.observe_elements(&public_input_hash.elements);
+
+    for i in 0..conf.num_wires_cap {
+        challenger.observe_hash::<C::Hasher>(hash(proof.wires_cap(i)));
+    }
+    let plonk_betas = challenger.get_n_challenges(num_challenges);
+    let plonk_gammas = challenger.get_n_challenges(num_challenges);
+
+    for i in 0..conf.num_plonk_zs_partial_products_cap {
+        challenger.observe_hash::<C::Hasher>(hash(proof.plonk_zs_partial_products_cap(i)));
+    }
+    let plonk_alphas = challenger.get_n_challenges(num_challenges);
+
+    for i in 0..conf.num_quotient_polys_cap {
+        challenger.observe_hash::<C::Hasher>(hash(proof.quotient_polys_cap(i)));
+    }
+    let plonk_zeta = challenger.get_extension_challenge::<D>();
+
+    for opening in [
+        proof.openings_constants(),
+        proof.openings_plonk_sigmas(),
+        proof.openings_wires(),
+        proof.openings_plonk_zs(),
+        proof.openings_partial_products(),
+        proof.openings_quotient_polys(),
+        proof.openings_plonk_zs_next(),
+    ] {
+        for e in opening {
+            challenger.observe_extension_element::<D>(&e);
+        }
+    }
+
+    // Fri Challenges
+    let fri_alpha = challenger.get_extension_challenge::<D>();
+    let mut fri_betas = Vec::with_capacity(conf.num_fri_commit_round);
+    for i in 0..conf.num_fri_commit_round {
+        for j in 0..conf.fri_commit_merkle_cap_height {
+            challenger.observe_hash::<C::Hasher>(hash(proof.fri_commit_phase_merkle_caps(i, j)));
+        }
+        fri_betas.push(challenger.get_extension_challenge::<D>());
+    }
+
+    for e in proof.fri_final_poly_ext_v() {
+        challenger.observe_extension_element::<D>(&e);
+    }
+
+    // Same as `get_fri_pow_response`, the witness bytes are hashed as they are in the proof.
+    let mut pow_input = Vec::with_capacity(5 * conf.field_size);
+    for u in challenger.get_n_challenges(4) {
+        pow_input.extend_from_slice(&u.to_canonical_u64().to_le_bytes());
+    }
+    pow_input.extend_from_slice(proof.fri_pow_witness());
+    let h = keccak_hash::keccak(pow_input).0;
+    let fri_pow_response = u64::from_le_bytes(h[..8].try_into().unwrap());
+
+    let lde_size = 1u32 << (common.degree_bits + common.config.fri_config.rate_bits);
+    let fri_query_indices = (0..conf.num_fri_query_round)
+        .map(|_| (challenger.get_challenge().to_canonical_u64() as u32 % lde_size) as usize)
+        .collect();
+
+    ProofChallenges {
+        plonk_betas,
+        plonk_gammas,
+        plonk_alphas,
+        plonk_zeta,
+        fri_alpha,
+        fri_betas,
+        fri_pow_response,
+        fri_query_indices,
+        public_input_hash,
+    }
+}
+
+fn eval_l_1<F: Field>(n: u64, x: F) -> F {
+    if x.is_one() {
+        return x;
+    }
+    (x.exp_u64(n) - F::ONE) / (F::from_canonical_u64(n) * (x - F::ONE))
+}
+
+/// Horner evaluation of `sum terms[i] * alpha^i`.
+fn reduce_with_powers<F: Field>(terms: &[F], alpha: F) -> F {
+    terms
+        .iter()
+        .rev()
+        .fold(F::ZERO, |sum, &term| sum * alpha + term)
+}
+
+/// The vanishing polynomial at zeta of each challenge, combined with the alphas.
+fn eval_vanishing_poly<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    proof: &FlatProof<F, D>,
+    challenges: &ProofChallenges<F, D>,
+    common: &CommonCircuitData<F, C, D>,
+) -> Vec<F::Extension> {
+    let num_challenges = common.config.num_challenges;
+    let zeta = challenges.plonk_zeta;
+    let constants = proof.openings_constants();
+    let wires = proof.openings_wires();
+    let sigmas = proof.openings_plonk_sigmas();
+    let zs = proof.openings_plonk_zs();
+    let zs_next = proof.openings_plonk_zs_next();
+    let partial_products = proof.openings_partial_products();
+
+    let vars = EvaluationVars {
+        local_constants: &constants,
+        local_wires: &wires,
+        public_inputs_hash: &challenges.public_input_hash,
+    };
+    let mut constraint_terms = vec![F::Extension::ZERO; common.num_gate_constraints];
+    let num_selectors = common.selectors_info.num_selectors();
+    for (row, gate) in common.gates.iter().enumerate() {
+        let selector_index = common.selectors_info.selector_indices[row];
+        let gate_constraints = gate.0.eval_filtered(
+            vars,
+            row,
+            selector_index,
+            common.selectors_info.groups[selector_index].clone(),
+            num_selectors,
+        );
+        for (term, c) in constraint_terms.iter_mut().zip(gate_constraints) {
+            *term += c;
+        }
+    }
+
+    let l1_x = eval_l_1(1u64 << common.degree_bits, zeta);
+    let num_partial_products = partial_products.len() / num_challenges;
+    let mut vanishing_z_1_terms = Vec::with_capacity(num_challenges);
+    let mut vanishing_partial_products_terms = Vec::new();
+    for i in 0..num_challenges {
+        let z_x = zs[i];
+        vanishing_z_1_terms.push(l1_x * (z_x - F::Extension::ONE));
+
+        let beta = F::Extension::from_basefield(challenges.plonk_betas[i]);
+        let gamma = F::Extension::from_basefield(challenges.plonk_gammas[i]);
+        let mut numerator_values = Vec::with_capacity(sigmas.len());
+        let mut denominator_values = Vec::with_capacity(sigmas.len());
+        for (j, &s_sigma) in sigmas.iter().enumerate() {
+            let s_id = zeta * F::Extension::from_basefield(common.k_is[j]);
+            numerator_values.push(wires[j] + s_id * beta + gamma);
+            denominator_values.push(wires[j] + s_sigma * beta + gamma);
+        }
+
+        let mut accs = vec![z_x];
+        accs.extend_from_slice(
+            &partial_products[i * num_partial_products..(i + 1) * num_partial_products],
+        );
+        accs.push(zs_next[i]);
+        for (j, (num_chunk, den_chunk)) in numerator_values
+            .chunks(common.quotient_degree_factor)
+            .zip(denominator_values.chunks(common.quotient_degree_factor))
+            .enumerate()
+        {
+            let num_prod: F::Extension = num_chunk.iter().copied().product();
+            let den_prod: F::Extension = den_chunk.iter().copied().product();
+            vanishing_partial_products_terms.push(accs[j] * num_prod - accs[j + 1] * den_prod);
+        }
+    }
+
+    let vanishing_terms = [
+        vanishing_z_1_terms,
+        vanishing_partial_products_terms,
+        constraint_terms,
+    ]
+    .concat();
+    challenges
+        .plonk_alphas
+        .iter()
+        .map(|&alpha| reduce_with_powers(&vanishing_terms, F::Extension::from_basefield(alpha)))
+        .collect()
+}
+
+/// Barycentric interpolation at `x` of the coset `points` with the given values.
+fn interpolate<F: RichField + Extendable<D>, const D: usize>(
+    points: &[F],
+    values: &[F::Extension],
+    x: F::Extension,
+) -> F::Extension {
+    let mut l_x = F::Extension::ONE;
+    let mut sum = F::Extension::ZERO;
+    for (j, (&point, &value)) in points.iter().zip(values).enumerate() {
+        let weight = points
+            .iter()
+            .enumerate()
+            .filter(|&(k, _)| k != j)
+            .map(|(_, &p)| point - p)
+            .product::<F>()
+            .inverse();
+        let x_minus_point = x - F::Extension::from_basefield(point);
+        l_x *= x_minus_point;
+        sum += F::Extension::from_basefield(weight) / x_minus_point * value;
+    }
+    l_x * sum
+}
+
+fn verify_fri_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    proof: &FlatProof<F, D>,
+    challenges: &ProofChallenges<F, D>,
+    common: &CommonCircuitData<F, C, D>,
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+) -> Result<()> {
+    let conf = proof.conf;
+    let num_challenges = common.config.num_challenges;
+    let alpha = challenges.fri_alpha;
+    let zeta = challenges.plonk_zeta;
+
+    // Precomputed reduced openings
+    let reduced_openings = reduce_with_powers(
+        &[
+            proof.openings_constants(),
+            proof.openings_plonk_sigmas(),
+            proof.openings_wires(),
+            proof.openings_plonk_zs(),
+            proof.openings_partial_products(),
+            proof.openings_quotient_polys(),
+        ]
+        .concat(),
+        alpha,
+    );
+    let reduced_openings_next = reduce_with_powers(&proof.openings_plonk_zs_next(), alpha);
+    let zeta_next = F::Extension::primitive_root_of_unity(common.degree_bits) * zeta;
+
+    let log_n = log2_strict(common.fri_params.lde_size());
+    let reduction_arity_bits = &common.fri_params.reduction_arity_bits;
+    let final_poly = proof.fri_final_poly_ext_v();
+    let sigma_cap = |i: usize| verifier_only.constants_sigmas_cap.0[i].to_bytes();
+
+    for (round, &index) in challenges.fri_query_indices.iter().enumerate() {
+        let round_ptr = proof.layout.fri_query_round(round);
+        ensure!(
+            proof.verify_merkle_proof_to_cap(
+                round_ptr + proof.layout.init_constants_sigmas_v_ptr,
+                round_ptr + proof.layout.init_constants_sigmas_p_ptr,
+                conf.num_fri_query_init_constants_sigmas_p,
+                index,
+                sigma_cap,
+            ),
+            "constants and sigmas Merkle proof of query round {} failed",
+            round
+        );
+        ensure!(
+            proof.verify_merkle_proof_to_cap(
+                round_ptr + proof.layout.init_wires_v_ptr,
+                round_ptr + proof.layout.init_wires_p_ptr,
+                conf.num_fri_query_init_wires_p,
+                index,
+                |i| proof.wires_cap(i).to_vec(),
+            ),
+            "wires Merkle proof of query round {} failed",
+            round
+        );
+        ensure!(
+            proof.verify_merkle_proof_to_cap(
+                round_ptr + proof.layout.init_zs_partial_v_ptr,
+                round_ptr + proof.layout.init_zs_partial_p_ptr,
+                conf.num_fri_query_init_zs_partial_p,
+                index,
+                |i| proof.plonk_zs_partial_products_cap(i).to_vec(),
+            ),
+            "zs and partial products Merkle proof of query round {} failed",
+            round
+        );
+        ensure!(
+            proof.verify_merkle_proof_to_cap(
+                round_ptr + proof.layout.init_quotient_v_ptr,
+                round_ptr + proof.layout.init_quotient_p_ptr,
+                conf.num_fri_query_init_quotient_p,
+                index,
+                |i| proof.quotient_polys_cap(i).to_vec(),
+            ),
+            "quotient Merkle proof of query round {} failed",
+            round
+        );
+
+        let mut subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
+            * F::primitive_root_of_unity(log_n).exp_u64(reverse_bits(index, log_n) as u64);
+        let x = F::Extension::from_basefield(subgroup_x);
+
+        // The constants and sigmas oracle is never blinded, the salts of the others are skipped.
+        let wires_v = proof.fri_query_init_wires_v(round);
+        let zs_partial_v = proof.fri_query_init_zs_partial_v(round);
+        let quotient_v = proof.fri_query_init_quotient_v(round);
+        let init_values = [
+            &proof.fri_query_init_constants_sigmas_v(round)[..],
+            &wires_v[..wires_v.len() - conf.salt_size],
+            &zs_partial_v[..zs_partial_v.len() - conf.salt_size],
+            &quotient_v[..quotient_v.len() - conf.salt_size],
+        ]
+        .concat()
+        .into_iter()
+        .map(F::Extension::from_basefield)
+        .collect::<Vec<_>>();
+        let zs_values = zs_partial_v[..num_challenges]
+            .iter()
+            .copied()
+            .map(F::Extension::from_basefield)
+            .collect::<Vec<_>>();
+
+        let mut sum = (reduce_with_powers(&init_values, alpha) - reduced_openings) / (x - zeta);
+        sum = alpha.exp_u64(num_challenges as u64) * sum
+            + (reduce_with_powers(&zs_values, alpha) - reduced_openings_next) / (x - zeta_next);
+        let mut old_eval = sum * x;
+
+        let mut index = index;
+        for (i, &arity_bits) in reduction_arity_bits.iter().enumerate() {
+            let arity = 1 << arity_bits;
+            let coset_index = index >> arity_bits;
+            let x_index_within_coset = index & (arity - 1);
+            let evals = proof.fri_query_step_v(round, i);
+            ensure!(
+                evals[x_index_within_coset] == old_eval,
+                "FRI step {} of query round {} is inconsistent",
+                i,
+                round
+            );
+
+            let g_arity = F::primitive_root_of_unity(arity_bits);
+            let rev_x_index_within_coset = reverse_bits(x_index_within_coset, arity_bits);
+            let mut points =
+                vec![subgroup_x * g_arity.exp_u64((arity - rev_x_index_within_coset) as u64)];
+            for k in 1..arity {
+                points.push(points[k - 1] * g_arity);
+            }
+            let values = (0..arity)
+                .map(|k| evals[reverse_bits(k, arity_bits)])
+                .collect::<Vec<_>>();
+            old_eval = interpolate::<F, D>(&points, &values, challenges.fri_betas[i]);
+
+            ensure!(
+                proof.verify_merkle_proof_to_cap(
+                    round_ptr + proof.layout.fri_query_step_v_ptrs[i],
+                    round_ptr + proof.layout.fri_query_step_p_ptrs[i],
+                    conf.num_fri_query_step_p[i],
+                    coset_index,
+                    |j| proof.fri_commit_phase_merkle_caps(i, j).to_vec(),
+                ),
+                "FRI step {} Merkle proof of query round {} failed",
+                i,
+                round
+            );
+
+            // Update the point x to x^arity.
+            subgroup_x = subgroup_x.exp_power_of_2(arity_bits);
+            index = coset_index;
+        }
+
+        // Final check of FRI. After all the reductions, we check that the final polynomial is equal
+        // to the one sent by the prover.
+        let final_eval = reduce_with_powers(&final_poly, F::Extension::from_basefield(subgroup_x));
+        ensure!(
+            old_eval == final_eval,
+            "final polynomial check of query round {} failed",
+            round
+        );
+    }
+
+    Ok(())
+}
+
+/// Verifies the proof bytes passed to `verify(bytes)` the way the generated contract does: the
+/// sections are read at the `ProofLib` offsets and the same challenges, vanishing polynomial and
+/// FRI checks as `template_main.sol` are performed.
+pub fn verify_proof_bytes<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    conf: &VerifierConfig,
+    common: &CommonCircuitData<F, C, D>,
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+    proof_bytes: &[u8],
+) -> Result<()> {
+    let proof_size = proof_size(conf);
+    if proof_bytes.len() != proof_size {
+        return Err(GeneratorError::LayoutMismatch {
+            section: "proof",
+            expected: proof_size,
+            actual: proof_bytes.len(),
+        }
+        .into());
+    }
+    let proof = FlatProof::<F, D>::new(conf, proof_bytes);
+    let challenges = get_challenges(&proof, common);
+
+    let min_fri_pow_response =
+        common.config.fri_config.proof_of_work_bits + (64 - F::order().bits()) as u32;
+    ensure!(
+        challenges.fri_pow_response.leading_zeros() >= min_fri_pow_response,
+        "insufficient proof of work"
+    );
+
+    let vanishing_polys_zeta = eval_vanishing_poly(&proof, &challenges, common);
+    let zeta_pow_deg = challenges.plonk_zeta.exp_power_of_2(common.degree_bits);
+    let z_h_zeta = zeta_pow_deg - F::Extension::ONE;
+    let quotient_polys = proof.openings_quotient_polys();
+    for (i, chunk) in quotient_polys
+        .chunks(common.quotient_degree_factor)
+        .enumerate()
+    {
+        ensure!(
+            vanishing_polys_zeta[i] == z_h_zeta * reduce_with_powers(chunk, zeta_pow_deg),
+            "vanishing polynomial check of challenge {} failed",
+            i
+        );
+    }
+
+    verify_fri_proof(&proof, &challenges, common, verifier_only)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::plonk::config::GenericConfig;
+
+    use crate::config::KeccakGoldilocksConfig2;
+    use crate::reference::{verify_proof_bytes, FlatProof};
+    use crate::test_utils::dummy_proof;
+    use crate::verifier::generate_verifier_config;
+    use crate::wrap::final_config;
+
+    #[test]
+    fn test_verify_proof_bytes() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+
+        let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config(), 4_000, 4)?;
+        let conf = generate_verifier_config(&proof)?;
+        let proof_bytes = proof.to_bytes()?;
+        verify_proof_bytes(&conf, &cd, &vd, &proof_bytes)?;
+
+        // Tampering with a public input, a query round or the final polynomial must fail.
+        let flat_proof = FlatProof::<F, D>::new(&conf, &proof_bytes);
+        for ptr in [
+            proof_bytes.len() - 1,
+            flat_proof.layout.fri_query_round(1) + flat_proof.layout.init_wires_v_ptr,
+            flat_proof.layout.fri_final_poly_ext_v_ptr,
+        ] {
+            let mut tampered = proof_bytes.clone();
+            tampered[ptr] ^= 1;
+            assert!(verify_proof_bytes(&conf, &cd, &vd, &tampered).is_err());
+        }
+        assert!(verify_proof_bytes(&conf, &cd, &vd, &proof_bytes[1..]).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,568 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::error::GeneratorError;
+use crate::verifier::encode_hex;
+
+/// A Solidity expression.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Expr {
+    /// A decimal integer literal.
+    Uint(u128),
+    /// A `0x` prefixed hex literal, e.g. a `bytes25` constant.
+    Hex(Vec<u8>),
+    /// A string literal, escaped when emitted.
+    Str(String),
+    /// An identifier or a type name.
+    Ident(String),
+    /// An array literal, e.g. `[uint64(1), 2]`.
+    Array(Vec<Expr>),
+    /// `base.member`
+    Member(Box<Expr>, String),
+    /// `base[index]`
+    Index(Box<Expr>, Box<Expr>),
+    /// `callee(args)`
+    Call(Box<Expr>, Vec<Expr>),
+    /// `left op right`, operands which are themselves binary expressions are parenthesized.
+    Binary(Box<Expr>, &'static str, Box<Expr>),
+}
+
+impl Expr {
+    pub fn ident(name: &str) -> Self {
+        Expr::Ident(name.to_owned())
+    }
+
+    pub fn member(self, name: &str) -> Self {
+        Expr::Member(Box::new(self), name.to_owned())
+    }
+
+    pub fn index<I: Into<Expr>>(self, index: I) -> Self {
+        Expr::Index(Box::new(self), Box::new(index.into()))
+    }
+
+    pub fn call(self, args: Vec<Expr>) -> Self {
+        Expr::Call(Box::new(self), args)
+    }
+
+    /// `self.name(args)`
+    pub fn method(self, name: &str, args: Vec<Expr>) -> Self {
+        self.member(name).call(args)
+    }
+
+    pub fn binary<R: Into<Expr>>(self, op: &'static str, right: R) -> Self {
+        Expr::Binary(Box::new(self), op, Box::new(right.into()))
+    }
+
+    /// A `uint64[N]` array literal, the first element is cast to fix the element type.
+    pub fn u64_array(values: &[u64]) -> Self {
+        Expr::Array(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| {
+                    if i == 0 {
+                        Expr::ident("uint64").call(vec![v.into()])
+                    } else {
+                        v.into()
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+macro_rules! impl_from_uint {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Expr {
+            fn from(v: $t) -> Self {
+                Expr::Uint(v as u128)
+            }
+        })*
+    };
+}
+
+impl_from_uint!(u8, u32, u64, usize);
+
+fn fmt_operand(f: &mut fmt::Formatter<'_>, e: &Expr) -> fmt::Result {
+    match e {
+        Expr::Binary(..) => write!(f, "({})", e),
+        _ => write!(f, "{}", e),
+    }
+}
+
+fn fmt_list(f: &mut fmt::Formatter<'_>, items: &[Expr]) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Uint(v) => write!(f, "{}", v),
+            Expr::Hex(bytes) => write!(f, "0x{}", encode_hex(bytes)),
+            Expr::Str(s) => {
+                write!(f, "\"")?;
+                for b in s.bytes() {
+                    match b {
+                        b'"' => write!(f, "\\\"")?,
+                        b'\\' => write!(f, "\\\\")?,
+                        b'\n' => write!(f, "\\n")?,
+                        b'\r' => write!(f, "\\r")?,
+                        b'\t' => write!(f, "\\t")?,
+                        0x20..=0x7e => write!(f, "{}", b as char)?,
+                        _ => write!(f, "\\x{:02x}", b)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Expr::Ident(name) => write!(f, "{}", name),
+            Expr::Array(items) => {
+                write!(f, "[")?;
+                fmt_list(f, items)?;
+                write!(f, "]")
+            }
+            Expr::Member(base, name) => write!(f, "{}.{}", base, name),
+            Expr::Index(base, index) => write!(f, "{}[{}]", base, index),
+            Expr::Call(callee, args) => {
+                write!(f, "{}(", callee)?;
+                fmt_list(f, args)?;
+                write!(f, ")")
+            }
+            Expr::Binary(left, op, right) => {
+                fmt_operand(f, left)?;
+                write!(f, " {} ", op)?;
+                fmt_operand(f, right)
+            }
+        }
+    }
+}
+
+/// A Solidity statement.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Stmt {
+    /// A `//` comment per line of the text.
+    Comment(String),
+    /// `target = value;`
+    Assign(Expr, Expr),
+    /// An expression statement, e.g. a call.
+    Expr(Expr),
+    /// `if (cond) then`
+    If(Expr, Box<Stmt>),
+    Return(Expr),
+    Revert,
+    /// A `{ ... }` scope.
+    Block(Vec<Stmt>),
+}
+
+impl Stmt {
+    /// Appends the statement to `out`, each line indented with `indent` and ending with a newline.
+    pub fn emit(&self, indent: &str, out: &mut String) {
+        match self {
+            Stmt::Comment(text) => {
+                for line in text.lines() {
+                    out.push_str(&format!("{}// {}\n", indent, line));
+                }
+            }
+            Stmt::Assign(target, value) => {
+                out.push_str(&format!("{}{} = {};\n", indent, target, value))
+            }
+            Stmt::Expr(e) => out.push_str(&format!("{}{};\n", indent, e)),
+            Stmt::If(cond, then) => match then.as_ref() {
+                Stmt::Block(body) => {
+                    out.push_str(&format!("{}if ({}) {{\n", indent, cond));
+                    emit_statements(body, &format!("{}    ", indent), out);
+                    out.push_str(&format!("{}}}\n", indent));
+                }
+                _ => {
+                    let mut then_str = String::new();
+                    then.emit("", &mut then_str);
+                    out.push_str(&format!("{}if ({}) {}", indent, cond, then_str));
+                }
+            },
+            Stmt::Return(e) => out.push_str(&format!("{}return {};\n", indent, e)),
+            Stmt::Revert => out.push_str(&format!("{}revert();\n", indent)),
+            Stmt::Block(body) => {
+                out.push_str(&format!("{}{{\n", indent));
+                emit_statements(body, &format!("{}    ", indent), out);
+                out.push_str(&format!("{}}}\n", indent));
+            }
+        }
+    }
+}
+
+pub fn emit_statements(statements: &[Stmt], indent: &str, out: &mut String) {
+    for statement in statements {
+        statement.emit(indent, out);
+    }
+}
+
+/// `name[i] = values[i];` for each value.
+pub fn array_assignments<T: Into<Expr>, I: IntoIterator<Item = T>>(
+    name: &str,
+    values: I,
+) -> Vec<Stmt> {
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| Stmt::Assign(Expr::ident(name).index(i), v.into()))
+        .collect()
+}
+
+/// `ty constant name = value;`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Constant {
+    pub ty: String,
+    pub name: String,
+    pub value: Expr,
+}
+
+impl Constant {
+    pub fn emit(&self, indent: &str, out: &mut String) {
+        out.push_str(&format!(
+            "{}{} constant {} = {};\n",
+            indent, self.ty, self.name, self.value
+        ));
+    }
+}
+
+/// A function definition, `params` and `returns` are `(type, name)` pairs.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub returns: Vec<(String, String)>,
+    /// Visibility and state mutability, e.g. `internal pure`.
+    pub modifiers: String,
+    pub body: Vec<Stmt>,
+}
+
+impl Function {
+    pub fn emit(&self, indent: &str, out: &mut String) {
+        let join = |vars: &[(String, String)]| {
+            vars.iter()
+                .map(|(ty, name)| {
+                    if name.is_empty() {
+                        ty.clone()
+                    } else {
+                        format!("{} {}", ty, name)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        out.push_str(&format!(
+            "{}function {}({}) {}",
+            indent,
+            self.name,
+            join(&self.params),
+            self.modifiers
+        ));
+        if !self.returns.is_empty() {
+            out.push_str(&format!(" returns ({})", join(&self.returns)));
+        }
+        out.push_str(" {\n");
+        emit_statements(&self.body, &format!("{}    ", indent), out);
+        out.push_str(&format!("{}}}\n", indent));
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Fragment {
+    Expr(String),
+    Statements(Vec<Stmt>),
+}
+
+/// A Solidity source whose `$NAME` placeholders are filled with expressions or statements.
+///
+/// Placeholders are matched as whole tokens, so `$MAX_ARITY` never replaces a prefix of
+/// `$MAX_ARITY_BITS`. Setting a placeholder the source does not contain is an error.
+#[derive(Debug, Clone)]
+pub struct Template {
+    name: String,
+    source: String,
+    values: BTreeMap<String, Fragment>,
+}
+
+/// The placeholder token starting at `source[i]`, without its `$`.
+fn placeholder_at(source: &str, i: usize) -> Option<&str> {
+    let rest = source[i..].strip_prefix('$')?;
+    if !rest.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return None;
+    }
+    let end = rest
+        .find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+        .unwrap_or(rest.len());
+    Some(&rest[..end])
+}
+
+/// Every `$NAME` placeholder in `source`, without its `$`.
+pub fn placeholders(source: &str) -> BTreeSet<String> {
+    source
+        .match_indices('$')
+        .filter_map(|(i, _)| placeholder_at(source, i))
+        .map(|p| p.to_owned())
+        .collect()
+}
+
+impl Template {
+    /// `name` identifies the template in errors, e.g. its file name.
+    pub fn new(name: &str, source: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            source: source.to_owned(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn contains(&self, placeholder: &str) -> bool {
+        placeholders(&self.source).contains(placeholder)
+    }
+
+    fn insert(
+        &mut self,
+        placeholder: &str,
+        fragment: Fragment,
+    ) -> Result<&mut Self, GeneratorError> {
+        if !self.contains(placeholder) {
+            return Err(GeneratorError::UnknownPlaceholder {
+                template: self.name.clone(),
+                placeholder: placeholder.to_owned(),
+            });
+        }
+        self.values.insert(placeholder.to_owned(), fragment);
+        Ok(self)
+    }
+
+    /// Replaces every `$placeholder` with `value`.
+    pub fn set<E: Into<Expr>>(
+        &mut self,
+        placeholder: &str,
+        value: E,
+    ) -> Result<&mut Self, GeneratorError> {
+        self.insert(placeholder, Fragment::Expr(value.into().to_string()))
+    }
+
+    /// Replaces the `$placeholder;` statement with `statements`, at the indentation of its line.
+    /// The line is removed if there are no statements.
+    pub fn set_statements(
+        &mut self,
+        placeholder: &str,
+        statements: Vec<Stmt>,
+    ) -> Result<&mut Self, GeneratorError> {
+        self.insert(placeholder, Fragment::Statements(statements))
+    }
+
+    /// Renders the template, failing if any placeholder is left in the output.
+    pub fn render_checked(&self) -> Result<String, GeneratorError> {
+        let out = self.render();
+        let left = placeholders(&out);
+        if !left.is_empty() {
+            return Err(GeneratorError::UnreplacedPlaceholders {
+                template: self.name.clone(),
+                placeholders: left.into_iter().collect(),
+            });
+        }
+        Ok(out)
+    }
+
+    /// The source with the placeholders set so far replaced. Others are left as is.
+    pub fn render(&self) -> String {
+        let source = &self.source;
+        let mut out = String::with_capacity(source.len());
+        let mut i = 0;
+        while i < source.len() {
+            let placeholder = match placeholder_at(source, i) {
+                Some(p) => p,
+                None => {
+                    let c = source[i..].chars().next().unwrap();
+                    out.push(c);
+                    i += c.len_utf8();
+                    continue;
+                }
+            };
+            i += 1 + placeholder.len();
+            match self.values.get(placeholder) {
+                None => {
+                    out.push('$');
+                    out.push_str(placeholder);
+                }
+                Some(Fragment::Expr(value)) => out.push_str(value),
+                Some(Fragment::Statements(statements)) => {
+                    if source[i..].starts_with(';') {
+                        i += 1;
+                    }
+                    let line_start = out.rfind('\n').map_or(0, |n| n + 1);
+                    let indent: String = out[line_start..]
+                        .chars()
+                        .take_while(|c| c.is_whitespace())
+                        .collect();
+                    if statements.is_empty() && indent.len() == out.len() - line_start {
+                        // Drop the whole line.
+                        out.truncate(line_start);
+                        if source[i..].starts_with('\n') {
+                            i += 1;
+                        }
+                        continue;
+                    }
+                    let mut code = String::new();
+                    emit_statements(statements, &indent, &mut code);
+                    // The first line is already indented in `out`. `code` is empty if there are
+                    // no statements, or only empty comments.
+                    let code = code.strip_prefix(indent.as_str()).unwrap_or(&code);
+                    out.push_str(code.trim_end_matches('\n'));
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::error::GeneratorError;
+    use crate::solidity::{
+        array_assignments, placeholders, Constant, Expr, Function, Stmt, Template,
+    };
+
+    #[test]
+    fn test_template() -> Result<()> {
+        let source = "contract C {
+    uint32 constant MAX_ARITY_BITS = $MAX_ARITY_BITS;
+    uint32 constant MAX_ARITY = $MAX_ARITY;
+
+    function f(uint64[2] memory k_is) internal pure {
+        $SET_K_IS;
+        $EMPTY;
+    }
+}
+";
+        assert_eq!(
+            placeholders(source).into_iter().collect::<Vec<_>>(),
+            ["EMPTY", "MAX_ARITY", "MAX_ARITY_BITS", "SET_K_IS"]
+        );
+
+        let mut template = Template::new("C.sol", source);
+        template
+            .set("MAX_ARITY", 8u32)?
+            .set("MAX_ARITY_BITS", 3u32)?;
+        template.set_statements("SET_K_IS", array_assignments("k_is", [1u64, 7]))?;
+        template.set_statements("EMPTY", vec![])?;
+        assert_eq!(
+            template.render(),
+            "contract C {
+    uint32 constant MAX_ARITY_BITS = 3;
+    uint32 constant MAX_ARITY = 8;
+
+    function f(uint64[2] memory k_is) internal pure {
+        k_is[0] = 1;
+        k_is[1] = 7;
+    }
+}
+"
+        );
+
+        assert!(template.render_checked().is_ok());
+
+        let err = template.set("MAX_ARITY_BIT", 3u32).unwrap_err();
+        assert!(matches!(err, GeneratorError::UnknownPlaceholder { .. }));
+
+        let mut template = Template::new("C.sol", source);
+        template.set("MAX_ARITY", 8u32)?;
+        match template.render_checked().unwrap_err() {
+            GeneratorError::UnreplacedPlaceholders {
+                template,
+                placeholders,
+            } => {
+                assert_eq!(template, "C.sol");
+                assert_eq!(placeholders, ["EMPTY", "MAX_ARITY_BITS", "SET_K_IS"]);
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_inline_statements() -> Result<()> {
+        let source = "    if (s == 0) { $INLINE; }\n";
+        let mut template = Template::new("C.sol", source);
+        template.set_statements("INLINE", vec![])?;
+        assert_eq!(template.render_checked()?, "    if (s == 0) {  }\n");
+        template.set_statements("INLINE", vec![Stmt::Comment("".to_owned())])?;
+        assert_eq!(template.render_checked()?, "    if (s == 0) {  }\n");
+        template.set_statements("INLINE", vec![Stmt::Revert])?;
+        assert_eq!(
+            template.render_checked()?,
+            "    if (s == 0) { revert(); }\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_emit() {
+        let filter = Expr::ident("GatesUtilsLib")
+            .method("field_ext_from", vec![1u32.into(), 0u32.into()])
+            .method(
+                "sub",
+                vec![Expr::ident("ev").member("constants").index(0u32)],
+            );
+        let function = Function {
+            name: "get_ptr".to_owned(),
+            params: vec![("uint32".to_owned(), "s".to_owned())],
+            returns: vec![("uint32".to_owned(), "".to_owned())],
+            modifiers: "internal pure".to_owned(),
+            body: vec![
+                Stmt::Comment("ArithmeticGate { num_ops: 20 }\nsecond line".to_owned()),
+                Stmt::If(
+                    Expr::ident("s").binary("==", 0u32),
+                    Box::new(Stmt::Return(25u32.into())),
+                ),
+                Stmt::Block(vec![Stmt::Assign(
+                    Expr::ident("ev").member("filter"),
+                    filter,
+                )]),
+                Stmt::Revert,
+            ],
+        };
+        let mut out = String::new();
+        Constant {
+            ty: "bytes25".to_owned(),
+            name: "DIGEST".to_owned(),
+            value: Expr::Hex(vec![0xab, 0x01]),
+        }
+        .emit("", &mut out);
+        function.emit("", &mut out);
+        assert_eq!(
+            out,
+            "bytes25 constant DIGEST = 0xab01;
+function get_ptr(uint32 s) internal pure returns (uint32) {
+    // ArithmeticGate { num_ops: 20 }
+    // second line
+    if (s == 0) return 25;
+    {
+        ev.filter = GatesUtilsLib.field_ext_from(1, 0).sub(ev.constants[0]);
+    }
+    revert();
+}
+"
+        );
+
+        assert_eq!(
+            Expr::Str("a \"b\"\n\u{e9}".to_owned()).to_string(),
+            "\"a \\\"b\\\"\\n\\xc3\\xa9\""
+        );
+        assert_eq!(Expr::u64_array(&[1, 2]).to_string(), "[uint64(1), 2]");
+        assert_eq!(
+            Expr::ident("a")
+                .binary("+", Expr::ident("b").binary("*", 2u32))
+                .to_string(),
+            "a + (b * 2)"
+        );
+    }
+}
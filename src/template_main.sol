@@ -1,8 +1,6 @@
 // SPDX-License-Identifier: UNLICENSED
 pragma solidity ^0.8.9;
 
-// Import this file to use console.log
-import "hardhat/console.sol";
 import "./Challenger.sol";
 import "./Plonk.sol";
 import "./GoldilocksField.sol";
@@ -24,6 +22,10 @@ contract Plonky2Verifier {
     uint32 constant NUM_GATE_CONSTRAINTS = $NUM_GATE_CONSTRAINTS;
     uint32 constant QUOTIENT_DEGREE_FACTOR = $QUOTIENT_DEGREE_FACTOR;
     uint32 constant NUM_REDUCTION_ARITY_BITS = $NUM_REDUCTION_ARITY_BITS;
+    uint32 constant MAX_ARITY_BITS = $MAX_ARITY_BITS;
+    uint32 constant MAX_ARITY = $MAX_ARITY;
+    // Number of salt values appended to the leaves of blinded oracles.
+    uint32 constant SALT_SIZE = $SALT_SIZE;
     uint32 constant NUM_SELECTORS = $NUM_SELECTORS;
 
     struct ProofChallenges {
@@ -47,11 +49,8 @@ contract Plonky2Verifier {
     }
 
     function get_g_by_arity_bits(uint32 arity_bits) internal pure returns (uint64) {
-        uint64[4] memory g_arity_bits;
-        g_arity_bits[0] = $G_ARITY_BITS_1;
-        g_arity_bits[1] = $G_ARITY_BITS_2;
-        g_arity_bits[2] = $G_ARITY_BITS_3;
-        g_arity_bits[3] = $G_ARITY_BITS_4;
+        uint64[MAX_ARITY_BITS] memory g_arity_bits;
+        $SET_G_ARITY_BITS;
         return g_arity_bits[arity_bits - 1];
     }
 
@@ -271,15 +270,14 @@ contract Plonky2Verifier {
     }
 
     function reduce2(bytes calldata proof, uint32 round, uint64[2] memory alpha) internal pure returns (uint64[2] memory evals) {
-        // bool constants_sigmas_blinding = false;
-        // bool other_oracles_blinding = $ZERO_KNOWLEDGE;
-        for (uint32 i = $NUM_FRI_QUERY_INIT_QUOTIENT_V; i > 0; i --) {
+        // The constants and sigmas oracle is never blinded, the salts of the others are skipped.
+        for (uint32 i = $NUM_FRI_QUERY_INIT_QUOTIENT_V - SALT_SIZE; i > 0; i --) {
             evals = le_bytes8_to_ext(proof.get_fri_query_init_quotient_v(round, i - 1)).add(evals.mul(alpha));
         }
-        for (uint32 i = $NUM_FRI_QUERY_INIT_ZS_PARTIAL_V; i > 0; i --) {
+        for (uint32 i = $NUM_FRI_QUERY_INIT_ZS_PARTIAL_V - SALT_SIZE; i > 0; i --) {
             evals = le_bytes8_to_ext(proof.get_fri_query_init_zs_partial_v(round, i - 1)).add(evals.mul(alpha));
         }
-        for (uint32 i = $NUM_FRI_QUERY_INIT_WIRES_V; i > 0; i --) {
+        for (uint32 i = $NUM_FRI_QUERY_INIT_WIRES_V - SALT_SIZE; i > 0; i --) {
             evals = le_bytes8_to_ext(proof.get_fri_query_init_wires_v(round, i - 1)).add(evals.mul(alpha));
         }
         for (uint32 i = $NUM_FRI_QUERY_INIT_CONSTANTS_SIGMAS_V; i > 0; i --) {
@@ -294,7 +292,7 @@ contract Plonky2Verifier {
     }
 
     // TODO: optimization barycentric_weights calculations
-    function cal_barycentric_weights(uint64[2][16] memory barycentric_weights, uint64[2][16] memory points, uint32 arity) internal view {
+    function cal_barycentric_weights(uint64[2][MAX_ARITY] memory barycentric_weights, uint64[2][MAX_ARITY] memory points, uint32 arity) internal view {
         barycentric_weights[0][0] = points[0][0].sub(points[1][0]);
         for (uint32 j = 2; j < arity; j++) {
             barycentric_weights[0][0] = barycentric_weights[0][0].mul(points[0][0].sub(points[j][0]));
@@ -312,7 +310,7 @@ contract Plonky2Verifier {
         }
     }
 
-    function get_points(uint64[2][16] memory points, uint32 arity_bits, uint32 x_index_within_coset, uint64 subgroup_x) internal view {
+    function get_points(uint64[2][MAX_ARITY] memory points, uint32 arity_bits, uint32 x_index_within_coset, uint64 subgroup_x) internal view {
         uint32 arity = uint32(1 << arity_bits);
         uint64 g_arity = get_g_by_arity_bits(arity_bits);
         uint32 rev_x_index_within_coset = reverse_bits(x_index_within_coset, arity_bits);
@@ -323,13 +321,13 @@ contract Plonky2Verifier {
     }
 
     struct ComputeEvaluationVars {
-        uint64[2][16] barycentric_weights;
+        uint64[2][MAX_ARITY] barycentric_weights;
         uint64[2] sum;
         uint64[2] l_x;
     }
 
     function compute_evaluation(bytes calldata proof, uint64[2] memory fri_beta, uint32 round, uint32 reduction,
-        uint32 arity_bits, uint64[2][16] memory points) internal view returns (uint64[2] memory){
+        uint32 arity_bits, uint64[2][MAX_ARITY] memory points) internal view returns (uint64[2] memory){
         ComputeEvaluationVars memory cv;
         cal_barycentric_weights(cv.barycentric_weights, points, uint32(1 << arity_bits));
 
@@ -340,13 +338,8 @@ contract Plonky2Verifier {
             cv.l_x = cv.l_x.mul(fri_beta.sub(points[i]));
         }
         for (uint32 i = 0; i < uint32(1 << arity_bits); i++) {
-            if (reduction == 0) {
-                cv.sum = cv.sum.add(cv.barycentric_weights[i].div(fri_beta.sub(points[i]))
-                .mul(le_bytes16_to_ext(proof.get_fri_query_step0_v(round, reverse_bits(i, arity_bits)))));
-            } else {
-                cv.sum = cv.sum.add(cv.barycentric_weights[i].div(fri_beta.sub(points[i]))
-                .mul(le_bytes16_to_ext(proof.get_fri_query_step1_v(round, reverse_bits(i, arity_bits)))));
-            }
+            cv.sum = cv.sum.add(cv.barycentric_weights[i].div(fri_beta.sub(points[i]))
+            .mul(le_bytes16_to_ext(proof.get_fri_query_step_v(round, reduction, reverse_bits(i, arity_bits)))));
         }
         return cv.l_x.mul(cv.sum);
     }
@@ -375,19 +368,19 @@ contract Plonky2Verifier {
         for (uint32 round = 0; round < $NUM_FRI_QUERY_ROUND; round++) {
             {
                 uint32 leaf_index = challenges.fri_query_indices[round];
-                if (proof.verify_merkle_proof_to_cap_init_constants_sigmas(round, leaf_index)) {
+                if (!proof.verify_merkle_proof_to_cap_init_constants_sigmas(round, leaf_index)) {
                     return false;
                 }
 
-                if (proof.verify_merkle_proof_to_cap_init_wires(round, leaf_index)) {
+                if (!proof.verify_merkle_proof_to_cap_init_wires(round, leaf_index)) {
                     return false;
                 }
 
-                if (proof.verify_merkle_proof_to_cap_init_zs_partial(round, leaf_index)) {
+                if (!proof.verify_merkle_proof_to_cap_init_zs_partial(round, leaf_index)) {
                     return false;
                 }
 
-                if (proof.verify_merkle_proof_to_cap_init_quotient(round, leaf_index)) {
+                if (!proof.verify_merkle_proof_to_cap_init_quotient(round, leaf_index)) {
                     return false;
                 }
             }
@@ -399,7 +392,7 @@ contract Plonky2Verifier {
                     reverse_bits(challenges.fri_query_indices[round], $LOG_SIZE_OF_LDE_DOMAIN)));
 
                 sum = challenges.fri_alpha.exp($NUM_FRI_QUERY_INIT_CONSTANTS_SIGMAS_V + $NUM_FRI_QUERY_INIT_WIRES_V +
-                $NUM_FRI_QUERY_INIT_ZS_PARTIAL_V + $NUM_FRI_QUERY_INIT_ZS_PARTIAL_V).mul(sum);
+                $NUM_FRI_QUERY_INIT_ZS_PARTIAL_V + $NUM_FRI_QUERY_INIT_QUOTIENT_V - 3 * SALT_SIZE).mul(sum);
                 sum = sum.add(reduce2(proof, round, challenges.fri_alpha).sub(vp.precomputed_reduced_evals[0])
                 .div(vp.subgroup_x.sub(challenges.plonk_zeta)));
 
@@ -414,23 +407,15 @@ contract Plonky2Verifier {
                 uint32 arity = uint32(1 << vp.arity_bits[i]);
                 uint32 coset_index = challenges.fri_query_indices[round] >> vp.arity_bits[i];
                 uint32 x_index_within_coset = challenges.fri_query_indices[round] & (arity - 1);
-                uint64[2] memory eval;
-                if (i == 0) {
-                    eval = le_bytes16_to_ext(proof.get_fri_query_step0_v(round, x_index_within_coset));
-                } else {
-                    eval = le_bytes16_to_ext(proof.get_fri_query_step1_v(round, x_index_within_coset));
-                }
+                uint64[2] memory eval = le_bytes16_to_ext(proof.get_fri_query_step_v(round, i, x_index_within_coset));
                 if (!eval.equal(vp.old_eval)) return false;
                 {
-                    uint64[2][16] memory points;
+                    uint64[2][MAX_ARITY] memory points;
                     get_points(points, vp.arity_bits[i], x_index_within_coset, vp.subgroup_x[0]);
                     vp.old_eval = compute_evaluation(proof, challenges.fri_betas[i], round, i, vp.arity_bits[i], points);
                 }
 
-                if (i == 0 && proof.verify_merkle_proof_to_cap_step0(round, coset_index)) {
-                    return false;
-                }
-                if (i == 1 && proof.verify_merkle_proof_to_cap_step1(round, coset_index)) {
+                if (!proof.verify_merkle_proof_to_cap_step(round, i, coset_index)) {
                     return false;
                 }
 
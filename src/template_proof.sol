@@ -46,9 +46,17 @@ library ProofLib {
         return bytes25(proof[$FRI_COMMIT_PHASE_MERKLE_CAPS_PTR + i * $FRI_COMMIT_ROUND_SIZE + j * 25 :]);
     }
 
+    // The leaf values end one byte before `p_start`, which holds the length of the Merkle proof.
+    // Returns the root of the path and the index of the cap element it must match.
     function get_fri_merkle_proof_to_cap(bytes calldata proof, uint32 v_start, uint32 p_start, uint32 merkle_proof_len,
         uint32 leaf_index) internal pure returns (bytes25, uint32) {
-        bytes32 current_digest = keccak256(proof[v_start : p_start]);
+        bytes32 current_digest;
+        if (p_start - 1 - v_start <= 25) {
+            // Leaves that fit in a hash are not hashed, but zero padded.
+            current_digest = bytes32(bytes25(proof[v_start : p_start - 1]));
+        } else {
+            current_digest = keccak256(proof[v_start : p_start - 1]);
+        }
 
         for (uint32 i = 0; i < merkle_proof_len; i ++) {
             uint32 bit = leaf_index & 1;
@@ -137,38 +145,33 @@ library ProofLib {
         return hash == get_quotient_polys_cap(proof, new_leaf_index);
     }
 
-    function get_fri_query_step0_v(bytes calldata proof, uint32 r, uint32 i) internal pure returns (bytes16) {
-        return bytes16(proof[$FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + $STEP0_V_PTR + i * 16 :]);
+    function get_fri_query_step_v_ptr(uint32 s) internal pure returns (uint32) {
+        $GET_FRI_QUERY_STEP_V_PTR;
     }
 
-    function get_fri_query_step0_p(bytes calldata proof, uint32 r, uint32 i) internal pure returns (bytes25) {
-        return bytes25(proof[$FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + $STEP0_P_PTR + i * 25 :]);
+    function get_fri_query_step_p_ptr(uint32 s) internal pure returns (uint32) {
+        $GET_FRI_QUERY_STEP_P_PTR;
     }
 
-    function verify_merkle_proof_to_cap_step0(bytes calldata proof, uint32 r, uint32 leaf_index) internal pure returns (bool) {
-        bytes25 hash;
-        uint32 new_leaf_index;
-        (hash, new_leaf_index) = get_fri_merkle_proof_to_cap(proof, $FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + $STEP0_V_PTR,
-            $FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + $STEP0_P_PTR,
-            $NUM_FRI_QUERY_STEP0_P, leaf_index);
-        return hash == get_fri_commit_phase_merkle_caps(proof, 0, new_leaf_index);
+    function get_num_fri_query_step_p(uint32 s) internal pure returns (uint32) {
+        $GET_NUM_FRI_QUERY_STEP_P;
     }
 
-    function get_fri_query_step1_v(bytes calldata proof, uint32 r, uint32 i) internal pure returns (bytes16) {
-        return bytes16(proof[$FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + $STEP1_V_PTR + i * 16 :]);
+    function get_fri_query_step_v(bytes calldata proof, uint32 r, uint32 s, uint32 i) internal pure returns (bytes16) {
+        return bytes16(proof[$FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + get_fri_query_step_v_ptr(s) + i * 16 :]);
     }
 
-    function get_fri_query_step1_p(bytes calldata proof, uint32 r, uint32 i) internal pure returns (bytes25) {
-        return bytes25(proof[$FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + $STEP1_P_PTR + i * 25 :]);
+    function get_fri_query_step_p(bytes calldata proof, uint32 r, uint32 s, uint32 i) internal pure returns (bytes25) {
+        return bytes25(proof[$FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + get_fri_query_step_p_ptr(s) + i * 25 :]);
     }
 
-    function verify_merkle_proof_to_cap_step1(bytes calldata proof, uint32 r, uint32 leaf_index) internal pure returns (bool) {
+    function verify_merkle_proof_to_cap_step(bytes calldata proof, uint32 r, uint32 s, uint32 leaf_index) internal pure returns (bool) {
         bytes25 hash;
         uint32 new_leaf_index;
-        (hash, new_leaf_index) = get_fri_merkle_proof_to_cap(proof, $FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + $STEP1_V_PTR,
-            $FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + $STEP1_P_PTR,
-            $NUM_FRI_QUERY_STEP1_P, leaf_index);
-        return hash == get_fri_commit_phase_merkle_caps(proof, 1, new_leaf_index);
+        (hash, new_leaf_index) = get_fri_merkle_proof_to_cap(proof, $FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + get_fri_query_step_v_ptr(s),
+            $FRI_QUERY_ROUND_PTR + $FRI_QUERY_ROUND_SIZE * r + get_fri_query_step_p_ptr(s),
+            get_num_fri_query_step_p(s), leaf_index);
+        return hash == get_fri_commit_phase_merkle_caps(proof, s, new_leaf_index);
     }
 
     function get_fri_final_poly_ext_v(bytes calldata proof, uint32 i) internal pure returns (bytes16) {
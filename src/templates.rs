@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use crate::error::GeneratorError;
+
+pub const TEMPLATE_MAIN_FILE: &str = "template_main.sol";
+pub const TEMPLATE_GATES_FILE: &str = "template_gates.sol";
+pub const TEMPLATE_PROOF_FILE: &str = "template_proof.sol";
+
+/// The Solidity templates the verifier contract, gates library and proof library are generated from.
+///
+/// `SolidityTemplates::default` gives the templates compiled into the crate.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SolidityTemplates {
+    pub main: String,
+    pub gates: String,
+    pub proof: String,
+}
+
+impl Default for SolidityTemplates {
+    fn default() -> Self {
+        Self {
+            main: include_str!("template_main.sol").to_owned(),
+            gates: include_str!("template_gates.sol").to_owned(),
+            proof: include_str!("template_proof.sol").to_owned(),
+        }
+    }
+}
+
+impl SolidityTemplates {
+    /// Loads the templates from `dir`, which may contain any of `template_main.sol`,
+    /// `template_gates.sol` and `template_proof.sol`. Missing files fall back to the built-in templates.
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> anyhow::Result<Self> {
+        let dir = dir.as_ref();
+        let mut templates = Self::default();
+        for (file_name, template) in [
+            (TEMPLATE_MAIN_FILE, &mut templates.main),
+            (TEMPLATE_GATES_FILE, &mut templates.gates),
+            (TEMPLATE_PROOF_FILE, &mut templates.proof),
+        ] {
+            let path = dir.join(file_name);
+            if path.is_file() {
+                *template = std::fs::read_to_string(&path)
+                    .map_err(|source| GeneratorError::TemplateIo { path, source })?;
+            }
+        }
+        Ok(templates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use anyhow::Result;
+
+    use crate::templates::{SolidityTemplates, TEMPLATE_PROOF_FILE};
+
+    #[test]
+    fn test_templates_from_dir() -> Result<()> {
+        let dir = std::env::temp_dir().join("plonky2_solidity_templates");
+        std::fs::create_dir_all(&dir)?;
+        let mut file = std::fs::File::create(dir.join(TEMPLATE_PROOF_FILE))?;
+        file.write_all(b"library ProofLib {}")?;
+
+        let templates = SolidityTemplates::from_dir(&dir)?;
+        let default = SolidityTemplates::default();
+        assert_eq!(templates.proof, "library ProofLib {}");
+        assert_eq!(templates.main, default.main);
+        assert_eq!(templates.gates, default.gates);
+        assert!(default.main.contains("$SET_K_IS"));
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::Field;
+use plonky2::gates::noop::NoopGate;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::witness::{PartialWitness, Witness};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{
+    CircuitConfig, CircuitData, CommonCircuitData, VerifierOnlyCircuitData,
+};
+use plonky2::plonk::config::{GenericConfig, Hasher};
+use plonky2::plonk::proof::ProofWithPublicInputs;
+
+/// Builds a dummy circuit which should have roughly `num_dummy_gates` gates.
+pub(crate) fn dummy_circuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    config: &CircuitConfig,
+    num_dummy_gates: u64,
+    num_public_inputs: u64,
+) -> CircuitData<F, C, D>
+where
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+    for _ in 0..num_dummy_gates {
+        builder.add_gate(NoopGate, vec![]);
+    }
+    let pi = builder.add_virtual_targets(num_public_inputs as usize);
+    builder.register_public_inputs(&pi);
+    builder.build::<C>()
+}
+
+/// Creates a dummy proof of `dummy_circuit`, with public inputs `0..num_public_inputs`.
+pub(crate) fn dummy_proof<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    config: &CircuitConfig,
+    num_dummy_gates: u64,
+    num_public_inputs: u64,
+) -> Result<(
+    ProofWithPublicInputs<F, C, D>,
+    VerifierOnlyCircuitData<C, D>,
+    CommonCircuitData<F, C, D>,
+)>
+where
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    let data = dummy_circuit::<F, C, D>(config, num_dummy_gates, num_public_inputs);
+    let mut inputs = PartialWitness::new();
+    for (i, &t) in data.prover_only.public_inputs.iter().enumerate() {
+        inputs.set_target(t, F::from_canonical_usize(i));
+    }
+    let proof = data.prove(inputs)?;
+    data.verify(proof.clone())?;
+
+    Ok((proof, data.verifier_only, data.common))
+}
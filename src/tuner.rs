@@ -0,0 +1,218 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use anyhow::Result;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::fri::reduction_strategies::FriReductionStrategy;
+use plonky2::fri::FriConfig;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, CommonCircuitData, VerifierCircuitTarget};
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
+
+use crate::compatibility::check_evm_compatibility;
+use crate::config::KeccakGoldilocksConfig2;
+use crate::gas::estimate_verification_gas;
+use crate::verifier::{generate_verifier_config_from_common_data, proof_size, VerifierConfig};
+use crate::wrap::final_config;
+
+/// The final config parameters to enumerate. The number of query rounds is the smallest one
+/// reaching `security_bits` for each rate and proof of work.
+#[derive(Debug, Clone)]
+pub struct ConfigSearchSpace {
+    pub security_bits: usize,
+    pub rate_bits: Vec<usize>,
+    pub proof_of_work_bits: Vec<u32>,
+    pub cap_heights: Vec<usize>,
+    /// Configs without enough routed wires for the recursive verifier gates of the inner proof are
+    /// skipped.
+    pub num_routed_wires: Vec<usize>,
+    pub reduction_strategies: Vec<FriReductionStrategy>,
+}
+
+impl Default for ConfigSearchSpace {
+    fn default() -> Self {
+        Self {
+            security_bits: 100,
+            rate_bits: vec![6, 7, 8, 9],
+            proof_of_work_bits: vec![16, 20],
+            cap_heights: vec![0, 1, 2],
+            num_routed_wires: vec![37],
+            reduction_strategies: vec![FriReductionStrategy::MinSize(None)],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigCandidate {
+    pub config: CircuitConfig,
+    pub security_bits: usize,
+    pub proof_size: usize,
+    pub gas: u64,
+}
+
+/// Conjectured security of FRI with the given parameters.
+pub fn fri_security_bits(fri_config: &FriConfig) -> usize {
+    fri_config.rate_bits * fri_config.num_query_rounds + fri_config.proof_of_work_bits as usize
+}
+
+/// Enumerates the final configs of `space` wrapping proofs of `inner_common`, ranked by
+/// `estimate_verification_gas` and then proof size.
+pub fn search_final_configs<InnerC: GenericConfig<2, F = GoldilocksField>>(
+    inner_common: &CommonCircuitData<GoldilocksField, InnerC, 2>,
+    space: &ConfigSearchSpace,
+) -> Result<Vec<ConfigCandidate>>
+where
+    InnerC::Hasher: AlgebraicHasher<GoldilocksField>,
+{
+    search_final_configs_with_cost(inner_common, space, |conf, common| {
+        estimate_verification_gas(conf, common).total()
+    })
+}
+
+/// Same as `search_final_configs`, ranked by the gas given by `cost`.
+pub fn search_final_configs_with_cost<
+    InnerC: GenericConfig<2, F = GoldilocksField>,
+    Cost: Fn(&VerifierConfig, &CommonCircuitData<GoldilocksField, KeccakGoldilocksConfig2, 2>) -> u64,
+>(
+    inner_common: &CommonCircuitData<GoldilocksField, InnerC, 2>,
+    space: &ConfigSearchSpace,
+    cost: Cost,
+) -> Result<Vec<ConfigCandidate>>
+where
+    InnerC::Hasher: AlgebraicHasher<GoldilocksField>,
+{
+    let mut candidates = Vec::new();
+    for config in candidate_configs(space) {
+        let security_bits = fri_security_bits(&config.fri_config);
+        if security_bits < space.security_bits {
+            continue;
+        }
+        let common =
+            match recursive_common_data::<KeccakGoldilocksConfig2, InnerC>(inner_common, &config) {
+                Some(common) => common,
+                None => {
+                    log::debug!("Skipping {:?}, the recursive verifier does not fit", config);
+                    continue;
+                }
+            };
+        if !check_evm_compatibility(&common).is_compatible() {
+            continue;
+        }
+        let conf = generate_verifier_config_from_common_data(&common)?;
+        candidates.push(ConfigCandidate {
+            security_bits,
+            proof_size: proof_size(&conf),
+            gas: cost(&conf, &common),
+            config,
+        });
+    }
+    candidates.sort_by_key(|c| (c.gas, c.proof_size));
+    Ok(candidates)
+}
+
+fn candidate_configs(space: &ConfigSearchSpace) -> Vec<CircuitConfig> {
+    let base = final_config();
+    let mut configs = Vec::new();
+    for &rate_bits in &space.rate_bits {
+        for &proof_of_work_bits in &space.proof_of_work_bits {
+            let remaining_bits = space
+                .security_bits
+                .saturating_sub(proof_of_work_bits as usize);
+            let num_query_rounds = ((remaining_bits + rate_bits - 1) / rate_bits).max(1);
+            for &cap_height in &space.cap_heights {
+                for &num_routed_wires in &space.num_routed_wires {
+                    for reduction_strategy in &space.reduction_strategies {
+                        configs.push(CircuitConfig {
+                            num_routed_wires,
+                            fri_config: FriConfig {
+                                rate_bits,
+                                cap_height,
+                                proof_of_work_bits,
+                                reduction_strategy: reduction_strategy.clone(),
+                                num_query_rounds,
+                            },
+                            ..base.clone()
+                        });
+                    }
+                }
+            }
+        }
+    }
+    configs
+}
+
+/// Common data of the circuit verifying proofs of `inner_common`, built without proving. `None` if
+/// the recursive verifier gates do not fit in the wires of `config`.
+fn recursive_common_data<
+    C: GenericConfig<2, F = GoldilocksField>,
+    InnerC: GenericConfig<2, F = GoldilocksField>,
+>(
+    inner_common: &CommonCircuitData<GoldilocksField, InnerC, 2>,
+    config: &CircuitConfig,
+) -> Option<CommonCircuitData<GoldilocksField, C, 2>>
+where
+    InnerC::Hasher: AlgebraicHasher<GoldilocksField>,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    // The builder panics on gates with more wires, or more routed wires, than the config.
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut builder = CircuitBuilder::<GoldilocksField, 2>::new(config.clone());
+        let pt = builder.add_virtual_proof_with_pis(inner_common);
+        let inner_data = VerifierCircuitTarget {
+            constants_sigmas_cap: builder
+                .add_virtual_cap(inner_common.config.fri_config.cap_height),
+        };
+        builder.verify_proof(pt, &inner_data, inner_common);
+        builder.build::<C>().common
+    }))
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use crate::test_utils::dummy_circuit;
+    use crate::tuner::{
+        fri_security_bits, recursive_common_data, search_final_configs, ConfigSearchSpace,
+    };
+    use crate::wrap::{final_config, high_rate_config};
+
+    #[test]
+    fn test_search_final_configs() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let cd =
+            dummy_circuit::<F, C, D>(&CircuitConfig::standard_recursion_config(), 4_000, 0).common;
+        let cd = recursive_common_data::<C, C>(&cd, &high_rate_config()).unwrap();
+
+        let final_config = final_config();
+        assert_eq!(fri_security_bits(&final_config.fri_config), 100);
+        let space = ConfigSearchSpace {
+            rate_bits: vec![7, 8],
+            proof_of_work_bits: vec![20],
+            cap_heights: vec![0],
+            ..ConfigSearchSpace::default()
+        };
+        let candidates = search_final_configs(&cd, &space)?;
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates.iter().all(|c| c.security_bits >= 100));
+        assert!(candidates.windows(2).all(|w| w[0].gas <= w[1].gas));
+        assert!(candidates.iter().any(|c| c.config.fri_config.rate_bits == 8
+            && c.config.fri_config.num_query_rounds == final_config.fri_config.num_query_rounds));
+
+        // The Poseidon gates of the recursive verifier route more than 10 wires.
+        let space = ConfigSearchSpace {
+            num_routed_wires: vec![10, 37],
+            ..space
+        };
+        let candidates = search_final_configs(&cd, &space)?;
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates.iter().all(|c| c.config.num_routed_wires == 37));
+
+        Ok(())
+    }
+}
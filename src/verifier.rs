@@ -4,6 +4,7 @@ use anyhow::Result;
 use log::Level;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::types::Field;
+use plonky2::fri::oracle::SALT_SIZE;
 use plonky2::gates::noop::NoopGate;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::witness::{PartialWitness, Witness};
@@ -17,7 +18,16 @@ use plonky2::plonk::proof::ProofWithPublicInputs;
 use plonky2::plonk::prover::prove;
 use plonky2::util::timing::TimingTree;
 use plonky2_util::log2_strict;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use crate::compatibility::{check_evm_compatibility_with_gates, fri_shape_issue};
+use crate::error::GeneratorError;
+use crate::gates::GateRegistry;
+use crate::layout::ProofLayout;
+use crate::solidity::{array_assignments, Expr, Stmt, Template};
+use crate::templates::{
+    SolidityTemplates, TEMPLATE_GATES_FILE, TEMPLATE_MAIN_FILE, TEMPLATE_PROOF_FILE,
+};
 
 pub fn encode_hex(bytes: &[u8]) -> String {
     let mut s = String::with_capacity(bytes.len() * 2);
@@ -27,7 +37,9 @@ pub fn encode_hex(bytes: &[u8]) -> String {
     s
 }
 
-fn recursive_proof<
+/// Proves the verification of `inner_proof` in a circuit built with `config`, optionally padded
+/// to `min_degree_bits`.
+pub fn recursive_proof<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     InnerC: GenericConfig<D, F = F>,
@@ -86,59 +98,275 @@ where
         timing.print();
     }
 
-    println!("######################### recursive verify #########################");
+    log::info!("Verifying the recursive proof");
     data.verify(proof.clone())?;
 
     Ok((proof, data.verifier_only, data.common))
 }
 
-#[derive(Serialize)]
+// Sizes in bytes of a serialized hash, field element, extension field element and Merkle proof
+// length, as read by the generated contracts.
+const HASH_SIZE: usize = 25;
+const FIELD_SIZE: usize = 8;
+const EXT_FIELD_SIZE: usize = 16;
+const MERKLE_HEIGHT_SIZE: usize = 1;
+
+/// Sizes of every section of the proofs of a circuit, as read by the generated `ProofLib`.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 pub struct VerifierConfig {
-    hash_size: usize,
-    field_size: usize,
-    ext_field_size: usize,
-    merkle_height_size: usize,
+    pub(crate) hash_size: usize,
+    pub(crate) field_size: usize,
+    pub(crate) ext_field_size: usize,
+    pub(crate) merkle_height_size: usize,
 
-    num_wires_cap: usize,
-    num_plonk_zs_partial_products_cap: usize,
-    num_quotient_polys_cap: usize,
+    pub(crate) num_wires_cap: usize,
+    pub(crate) num_plonk_zs_partial_products_cap: usize,
+    pub(crate) num_quotient_polys_cap: usize,
 
     // openings
-    num_openings_constants: usize,
-    num_openings_plonk_sigmas: usize,
-    num_openings_wires: usize,
-    num_openings_plonk_zs: usize,
-    num_openings_plonk_zs_next: usize,
-    num_openings_partial_products: usize,
-    num_openings_quotient_polys: usize,
+    pub(crate) num_openings_constants: usize,
+    pub(crate) num_openings_plonk_sigmas: usize,
+    pub(crate) num_openings_wires: usize,
+    pub(crate) num_openings_plonk_zs: usize,
+    pub(crate) num_openings_plonk_zs_next: usize,
+    pub(crate) num_openings_partial_products: usize,
+    pub(crate) num_openings_quotient_polys: usize,
 
     // fri proof
     // .commit phase
-    num_fri_commit_round: usize,
-    fri_commit_merkle_cap_height: usize,
+    pub(crate) num_fri_commit_round: usize,
+    pub(crate) fri_commit_merkle_cap_height: usize,
     // .query round
-    num_fri_query_round: usize,
-    // ..init
-    num_fri_query_init_constants_sigmas_v: usize,
-    num_fri_query_init_constants_sigmas_p: usize,
-    num_fri_query_init_wires_v: usize,
-    num_fri_query_init_wires_p: usize,
-    num_fri_query_init_zs_partial_v: usize,
-    num_fri_query_init_zs_partial_p: usize,
-    num_fri_query_init_quotient_v: usize,
-    num_fri_query_init_quotient_p: usize,
+    pub(crate) num_fri_query_round: usize,
+    // ..init, the leaves of blinded oracles end with `salt_size` salt values
+    pub(crate) salt_size: usize,
+    pub(crate) num_fri_query_init_constants_sigmas_v: usize,
+    pub(crate) num_fri_query_init_constants_sigmas_p: usize,
+    pub(crate) num_fri_query_init_wires_v: usize,
+    pub(crate) num_fri_query_init_wires_p: usize,
+    pub(crate) num_fri_query_init_zs_partial_v: usize,
+    pub(crate) num_fri_query_init_zs_partial_p: usize,
+    pub(crate) num_fri_query_init_quotient_v: usize,
+    pub(crate) num_fri_query_init_quotient_p: usize,
     // ..steps
-    num_fri_query_step0_v: usize,
-    num_fri_query_step0_p: usize,
-    num_fri_query_step1_v: usize,
-    num_fri_query_step1_p: usize,
+    pub(crate) num_fri_query_step_v: Vec<usize>,
+    pub(crate) num_fri_query_step_p: Vec<usize>,
     // .final poly
-    num_fri_final_poly_ext_v: usize,
+    pub(crate) num_fri_final_poly_ext_v: usize,
     // public inputs
-    num_public_inputs: usize,
+    pub(crate) num_public_inputs: usize,
+}
+
+impl VerifierConfig {
+    pub fn hash_size(&self) -> usize {
+        self.hash_size
+    }
+
+    pub fn field_size(&self) -> usize {
+        self.field_size
+    }
+
+    pub fn ext_field_size(&self) -> usize {
+        self.ext_field_size
+    }
+
+    pub fn merkle_height_size(&self) -> usize {
+        self.merkle_height_size
+    }
+
+    pub fn num_wires_cap(&self) -> usize {
+        self.num_wires_cap
+    }
+
+    pub fn num_plonk_zs_partial_products_cap(&self) -> usize {
+        self.num_plonk_zs_partial_products_cap
+    }
+
+    pub fn num_quotient_polys_cap(&self) -> usize {
+        self.num_quotient_polys_cap
+    }
+
+    pub fn num_openings_constants(&self) -> usize {
+        self.num_openings_constants
+    }
+
+    pub fn num_openings_plonk_sigmas(&self) -> usize {
+        self.num_openings_plonk_sigmas
+    }
+
+    pub fn num_openings_wires(&self) -> usize {
+        self.num_openings_wires
+    }
+
+    pub fn num_openings_plonk_zs(&self) -> usize {
+        self.num_openings_plonk_zs
+    }
+
+    pub fn num_openings_plonk_zs_next(&self) -> usize {
+        self.num_openings_plonk_zs_next
+    }
+
+    pub fn num_openings_partial_products(&self) -> usize {
+        self.num_openings_partial_products
+    }
+
+    pub fn num_openings_quotient_polys(&self) -> usize {
+        self.num_openings_quotient_polys
+    }
+
+    pub fn num_fri_commit_round(&self) -> usize {
+        self.num_fri_commit_round
+    }
+
+    pub fn fri_commit_merkle_cap_height(&self) -> usize {
+        self.fri_commit_merkle_cap_height
+    }
+
+    pub fn num_fri_query_round(&self) -> usize {
+        self.num_fri_query_round
+    }
+
+    pub fn salt_size(&self) -> usize {
+        self.salt_size
+    }
+
+    pub fn num_fri_query_init_constants_sigmas_v(&self) -> usize {
+        self.num_fri_query_init_constants_sigmas_v
+    }
+
+    pub fn num_fri_query_init_constants_sigmas_p(&self) -> usize {
+        self.num_fri_query_init_constants_sigmas_p
+    }
+
+    pub fn num_fri_query_init_wires_v(&self) -> usize {
+        self.num_fri_query_init_wires_v
+    }
+
+    pub fn num_fri_query_init_wires_p(&self) -> usize {
+        self.num_fri_query_init_wires_p
+    }
+
+    pub fn num_fri_query_init_zs_partial_v(&self) -> usize {
+        self.num_fri_query_init_zs_partial_v
+    }
+
+    pub fn num_fri_query_init_zs_partial_p(&self) -> usize {
+        self.num_fri_query_init_zs_partial_p
+    }
+
+    pub fn num_fri_query_init_quotient_v(&self) -> usize {
+        self.num_fri_query_init_quotient_v
+    }
+
+    pub fn num_fri_query_init_quotient_p(&self) -> usize {
+        self.num_fri_query_init_quotient_p
+    }
+
+    pub fn num_fri_query_step_v(&self) -> &[usize] {
+        &self.num_fri_query_step_v
+    }
+
+    pub fn num_fri_query_step_p(&self) -> &[usize] {
+        &self.num_fri_query_step_p
+    }
+
+    pub fn num_fri_final_poly_ext_v(&self) -> usize {
+        self.num_fri_final_poly_ext_v
+    }
+
+    pub fn num_public_inputs(&self) -> usize {
+        self.num_public_inputs
+    }
+
+    /// Checks the parts of the config that do not depend on the circuit, e.g. after loading it from
+    /// `conf.json` without the circuit: the sizes of the serialized elements, which are fixed by
+    /// the generated contracts, and a Merkle proof per FRI step.
+    pub fn check(&self) -> Result<(), GeneratorError> {
+        for (field, expected, actual) in [
+            ("hash_size", HASH_SIZE, self.hash_size),
+            ("field_size", FIELD_SIZE, self.field_size),
+            ("ext_field_size", EXT_FIELD_SIZE, self.ext_field_size),
+            (
+                "merkle_height_size",
+                MERKLE_HEIGHT_SIZE,
+                self.merkle_height_size,
+            ),
+            (
+                "num_fri_query_step_p length",
+                self.num_fri_query_step_v.len(),
+                self.num_fri_query_step_p.len(),
+            ),
+        ] {
+            if expected != actual {
+                return Err(GeneratorError::ConfigMismatch {
+                    field: field.to_owned(),
+                    expected: expected.to_string(),
+                    actual: actual.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the config describes the proofs of `common`, e.g. after loading it from
+    /// `conf.json`. Returns the first field differing from the config derived from `common`.
+    pub fn validate<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+        &self,
+        common: &CommonCircuitData<F, C, D>,
+    ) -> anyhow::Result<()> {
+        let expected = generate_verifier_config_from_common_data(common)?;
+        // Destructuring without `..` makes sure that every field is compared.
+        macro_rules! compare_fields {
+            ($($field:ident),* $(,)?) => {
+                let VerifierConfig { $($field),* } = &expected;
+                $(
+                    if self.$field != *$field {
+                        return Err(GeneratorError::ConfigMismatch {
+                            field: stringify!($field).to_owned(),
+                            expected: format!("{:?}", $field),
+                            actual: format!("{:?}", self.$field),
+                        }
+                        .into());
+                    }
+                )*
+            };
+        }
+        compare_fields!(
+            hash_size,
+            field_size,
+            ext_field_size,
+            merkle_height_size,
+            num_wires_cap,
+            num_plonk_zs_partial_products_cap,
+            num_quotient_polys_cap,
+            num_openings_constants,
+            num_openings_plonk_sigmas,
+            num_openings_wires,
+            num_openings_plonk_zs,
+            num_openings_plonk_zs_next,
+            num_openings_partial_products,
+            num_openings_quotient_polys,
+            num_fri_commit_round,
+            fri_commit_merkle_cap_height,
+            num_fri_query_round,
+            salt_size,
+            num_fri_query_init_constants_sigmas_v,
+            num_fri_query_init_constants_sigmas_p,
+            num_fri_query_init_wires_v,
+            num_fri_query_init_wires_p,
+            num_fri_query_init_zs_partial_v,
+            num_fri_query_init_zs_partial_p,
+            num_fri_query_init_quotient_v,
+            num_fri_query_init_quotient_p,
+            num_fri_query_step_v,
+            num_fri_query_step_p,
+            num_fri_final_poly_ext_v,
+            num_public_inputs,
+        );
+        Ok(())
+    }
 }
 
-// TODO: The input should be CommonCircuitData
 pub fn generate_verifier_config<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -147,17 +375,17 @@ pub fn generate_verifier_config<
     pwpi: &ProofWithPublicInputs<F, C, D>,
 ) -> anyhow::Result<VerifierConfig> {
     let proof = &pwpi.proof;
-    assert_eq!(proof.opening_proof.query_round_proofs[0].steps.len(), 2);
-
-    const HASH_SIZE: usize = 25;
-    const FIELD_SIZE: usize = 8;
-    const EXT_FIELD_SIZE: usize = 16;
-    const MERKLE_HEIGHT_SIZE: usize = 1;
 
-    let query_round_init_trees = &proof.opening_proof.query_round_proofs[0]
-        .initial_trees_proof
-        .evals_proofs;
-    let query_round_steps = &proof.opening_proof.query_round_proofs[0].steps;
+    let query_round_proof = match proof.opening_proof.query_round_proofs.first() {
+        Some(p) => p,
+        None => {
+            return Err(
+                GeneratorError::UnsupportedFriShape("no FRI query rounds".to_owned()).into(),
+            )
+        }
+    };
+    let query_round_init_trees = &query_round_proof.initial_trees_proof.evals_proofs;
+    let query_round_steps = &query_round_proof.steps;
 
     let conf = VerifierConfig {
         hash_size: HASH_SIZE,
@@ -178,8 +406,10 @@ pub fn generate_verifier_config<
         num_openings_quotient_polys: proof.openings.quotient_polys.len(),
 
         num_fri_commit_round: proof.opening_proof.commit_phase_merkle_caps.len(),
-        fri_commit_merkle_cap_height: proof.opening_proof.commit_phase_merkle_caps[0].0.len(),
+        // All caps have the same length, which is also used when there is no reduction step.
+        fri_commit_merkle_cap_height: proof.wires_cap.0.len(),
         num_fri_query_round: proof.opening_proof.query_round_proofs.len(),
+        salt_size: query_round_init_trees[1].0.len() - proof.openings.wires.len(),
         num_fri_query_init_constants_sigmas_v: query_round_init_trees[0].0.len(),
         num_fri_query_init_constants_sigmas_p: query_round_init_trees[0].1.siblings.len(),
         num_fri_query_init_wires_v: query_round_init_trees[1].0.len(),
@@ -188,10 +418,11 @@ pub fn generate_verifier_config<
         num_fri_query_init_zs_partial_p: query_round_init_trees[2].1.siblings.len(),
         num_fri_query_init_quotient_v: query_round_init_trees[3].0.len(),
         num_fri_query_init_quotient_p: query_round_init_trees[3].1.siblings.len(),
-        num_fri_query_step0_v: query_round_steps[0].evals.len(),
-        num_fri_query_step0_p: query_round_steps[0].merkle_proof.siblings.len(),
-        num_fri_query_step1_v: query_round_steps[1].evals.len(),
-        num_fri_query_step1_p: query_round_steps[1].merkle_proof.siblings.len(),
+        num_fri_query_step_v: query_round_steps.iter().map(|s| s.evals.len()).collect(),
+        num_fri_query_step_p: query_round_steps
+            .iter()
+            .map(|s| s.merkle_proof.siblings.len())
+            .collect(),
         num_fri_final_poly_ext_v: proof.opening_proof.final_poly.coeffs.len(),
 
         num_public_inputs: pwpi.public_inputs.len(),
@@ -199,6 +430,99 @@ pub fn generate_verifier_config<
     Ok(conf)
 }
 
+/// Same as `generate_verifier_config`, but derived from the circuit alone so that the verifier
+/// can be generated before any proof exists.
+pub fn generate_verifier_config_from_common_data<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    common: &CommonCircuitData<F, C, D>,
+) -> anyhow::Result<VerifierConfig> {
+    let reduction_arity_bits = &common.fri_params.reduction_arity_bits;
+
+    let config = &common.config;
+    let num_challenges = config.num_challenges;
+    let cap_height = config.fri_config.cap_height;
+    let cap_len = 1 << cap_height;
+    // Blinded oracles append salt values to their Merkle leaves.
+    let salt_size = if config.zero_knowledge { SALT_SIZE } else { 0 };
+    let lde_bits = common.fri_params.lde_bits();
+    if let Some(issue) = fri_shape_issue(common) {
+        return Err(issue.into());
+    }
+    let merkle_proof_len = |arity_bits: usize| lde_bits - arity_bits - cap_height;
+
+    let conf = VerifierConfig {
+        hash_size: HASH_SIZE,
+        field_size: FIELD_SIZE,
+        ext_field_size: EXT_FIELD_SIZE,
+        merkle_height_size: MERKLE_HEIGHT_SIZE,
+
+        num_wires_cap: cap_len,
+        num_plonk_zs_partial_products_cap: cap_len,
+        num_quotient_polys_cap: cap_len,
+
+        num_openings_constants: common.num_constants,
+        num_openings_plonk_sigmas: config.num_routed_wires,
+        num_openings_wires: config.num_wires,
+        num_openings_plonk_zs: num_challenges,
+        num_openings_plonk_zs_next: num_challenges,
+        num_openings_partial_products: num_challenges * common.num_partial_products,
+        num_openings_quotient_polys: num_challenges * common.quotient_degree_factor,
+
+        num_fri_commit_round: reduction_arity_bits.len(),
+        fri_commit_merkle_cap_height: cap_len,
+        num_fri_query_round: config.fri_config.num_query_rounds,
+        salt_size,
+        num_fri_query_init_constants_sigmas_v: common.num_constants + config.num_routed_wires,
+        num_fri_query_init_constants_sigmas_p: merkle_proof_len(0),
+        num_fri_query_init_wires_v: config.num_wires + salt_size,
+        num_fri_query_init_wires_p: merkle_proof_len(0),
+        num_fri_query_init_zs_partial_v: num_challenges * (1 + common.num_partial_products)
+            + salt_size,
+        num_fri_query_init_zs_partial_p: merkle_proof_len(0),
+        num_fri_query_init_quotient_v: num_challenges * common.quotient_degree_factor + salt_size,
+        num_fri_query_init_quotient_p: merkle_proof_len(0),
+        num_fri_query_step_v: reduction_arity_bits.iter().map(|&a| 1 << a).collect(),
+        num_fri_query_step_p: (0..reduction_arity_bits.len())
+            .map(|i| merkle_proof_len(reduction_arity_bits[..=i].iter().sum()))
+            .collect(),
+        num_fri_final_poly_ext_v: common.fri_params.final_poly_len(),
+
+        num_public_inputs: common.num_public_inputs,
+    };
+    Ok(conf)
+}
+
+/// Size in bytes of the proofs described by `conf`, including their public inputs.
+pub fn proof_size(conf: &VerifierConfig) -> usize {
+    ProofLayout::new(conf).proof_size
+}
+
+/// The proof bytes passed to the verifier contract, checked against the layout of `conf`.
+pub fn generate_proof_bytes<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    pwpi: &ProofWithPublicInputs<F, C, D>,
+    conf: &VerifierConfig,
+) -> anyhow::Result<Vec<u8>> {
+    let proof_size = proof_size(conf);
+    let proof_bytes = pwpi.to_bytes()?;
+    if proof_bytes.len() != proof_size {
+        return Err(GeneratorError::LayoutMismatch {
+            section: "proof",
+            expected: proof_size,
+            actual: proof_bytes.len(),
+        }
+        .into());
+    }
+
+    Ok(proof_bytes)
+}
+
 pub fn generate_proof_base64<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -207,60 +531,44 @@ pub fn generate_proof_base64<
     pwpi: &ProofWithPublicInputs<F, C, D>,
     conf: &VerifierConfig,
 ) -> anyhow::Result<String> {
-    // total size: 75
-    let mut proof_size: usize =
-        (conf.num_wires_cap + conf.num_plonk_zs_partial_products_cap + conf.num_quotient_polys_cap)
-            * conf.hash_size;
-
-    // total size: 3355
-    proof_size += (conf.num_openings_constants
-        + conf.num_openings_plonk_sigmas
-        + conf.num_openings_wires
-        + conf.num_openings_plonk_zs
-        + conf.num_openings_plonk_zs_next
-        + conf.num_openings_partial_products
-        + conf.num_openings_quotient_polys)
-        * conf.ext_field_size;
-
-    // 3405
-    proof_size += (conf.num_fri_commit_round * conf.fri_commit_merkle_cap_height) * conf.hash_size;
-    // 39685
-    proof_size += conf.num_fri_query_round
-        * ((conf.num_fri_query_init_constants_sigmas_v
-            + conf.num_fri_query_init_wires_v
-            + conf.num_fri_query_init_zs_partial_v
-            + conf.num_fri_query_init_quotient_v)
-            * conf.field_size
-            + (conf.num_fri_query_init_constants_sigmas_p
-                + conf.num_fri_query_init_wires_p
-                + conf.num_fri_query_init_zs_partial_p
-                + conf.num_fri_query_init_quotient_p)
-                * conf.hash_size
-            + conf.merkle_height_size * 4);
-    // 50015
-    proof_size += conf.num_fri_query_round
-        * (conf.num_fri_query_step0_v * conf.ext_field_size
-            + conf.num_fri_query_step0_p * conf.hash_size
-            + conf.merkle_height_size
-            + conf.num_fri_query_step1_v * conf.ext_field_size
-            + conf.num_fri_query_step1_p * conf.hash_size
-            + conf.merkle_height_size);
-
-    // 51039
-    proof_size += conf.num_fri_final_poly_ext_v * conf.ext_field_size;
-
-    // 51047
-    proof_size += conf.field_size;
-
-    proof_size += conf.num_public_inputs * conf.field_size;
+    Ok(base64::encode(generate_proof_bytes(pwpi, conf)?))
+}
 
-    let proof_bytes = pwpi.to_bytes()?;
-    assert_eq!(proof_bytes.len(), proof_size);
+pub fn generate_solidity_verifier<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    conf: &VerifierConfig,
+    common: &CommonCircuitData<F, C, D>,
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+) -> anyhow::Result<(String, String, String)> {
+    generate_solidity_verifier_with_gates(conf, common, verifier_only, &GateRegistry::default())
+}
 
-    Ok(base64::encode(proof_bytes))
+/// Same as `generate_solidity_verifier`, with the Solidity code of each gate emitted by `gates`.
+pub fn generate_solidity_verifier_with_gates<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    conf: &VerifierConfig,
+    common: &CommonCircuitData<F, C, D>,
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+    gates: &GateRegistry<F, D>,
+) -> anyhow::Result<(String, String, String)> {
+    generate_solidity_verifier_with_templates(
+        conf,
+        common,
+        verifier_only,
+        gates,
+        &SolidityTemplates::default(),
+    )
 }
 
-pub fn generate_solidity_verifier<
+/// Same as `generate_solidity_verifier_with_gates`, generating the contracts from `templates`
+/// instead of the built-in ones. Fails if a `$PLACEHOLDER` is left in any of the outputs.
+pub fn generate_solidity_verifier_with_templates<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     const D: usize,
@@ -268,477 +576,324 @@ pub fn generate_solidity_verifier<
     conf: &VerifierConfig,
     common: &CommonCircuitData<F, C, D>,
     verifier_only: &VerifierOnlyCircuitData<C, D>,
+    gates: &GateRegistry<F, D>,
+    templates: &SolidityTemplates,
 ) -> anyhow::Result<(String, String, String)> {
-    assert_eq!(
-        25,
-        C::Hasher::HASH_SIZE,
-        "Only support KeccakHash<25> right now"
-    );
-    assert_eq!(F::BITS, 64);
-    assert_eq!(F::Extension::BITS, 128);
-    println!("Generating solidity verifier files ...");
+    check_evm_compatibility_with_gates(common, gates).into_result()?;
+    log::info!("Generating solidity verifier files ...");
 
     // Load template contract
-    let mut contract = std::fs::read_to_string("./src/template_main.sol")
-        .expect("Something went wrong reading the file");
+    let mut contract = Template::new(TEMPLATE_MAIN_FILE, &templates.main);
 
-    let k_is = &common.k_is;
-    let mut k_is_str = "".to_owned();
-    for i in 0..k_is.len() {
-        k_is_str += &*("        k_is[".to_owned()
-            + &*i.to_string()
-            + "] = "
-            + &*k_is[i].to_canonical_u64().to_string()
-            + ";\n");
-    }
-    contract = contract.replace("        $SET_K_IS;\n", &*k_is_str);
+    let k_is = common.k_is.iter().map(|k| k.to_canonical_u64());
+    contract.set_statements("SET_K_IS", array_assignments("k_is", k_is))?;
 
     let reduction_arity_bits = &common.fri_params.reduction_arity_bits;
-    let mut reduction_arity_bits_str = "".to_owned();
-    for i in 0..reduction_arity_bits.len() {
-        reduction_arity_bits_str += &*("        bits[".to_owned()
-            + &*i.to_string()
-            + "] = "
-            + &*reduction_arity_bits[i].to_string()
-            + ";\n");
-    }
-    contract = contract.replace(
-        "        $SET_REDUCTION_ARITY_BITS;\n",
-        &*reduction_arity_bits_str,
-    );
-    contract = contract.replace(
-        "$NUM_REDUCTION_ARITY_BITS",
-        &*reduction_arity_bits.len().to_string(),
-    );
-
-    contract = contract.replace("$NUM_WIRES_CAP", &*conf.num_wires_cap.to_string());
-    contract = contract.replace(
-        "$NUM_PLONK_ZS_PARTIAL_PRODUCTS_CAP",
-        &*conf.num_plonk_zs_partial_products_cap.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_QUOTIENT_POLYS_CAP",
-        &*conf.num_quotient_polys_cap.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_OPENINGS_CONSTANTS",
-        &*conf.num_openings_constants.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_OPENINGS_PLONK_SIGMAS",
-        &*conf.num_openings_plonk_sigmas.to_string(),
-    );
-    contract = contract.replace("$NUM_OPENINGS_WIRES", &*conf.num_openings_wires.to_string());
-    contract = contract.replace(
-        "$NUM_OPENINGS_PLONK_ZS0",
-        &*conf.num_openings_plonk_zs.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_OPENINGS_PLONK_ZS_NEXT",
-        &*conf.num_openings_plonk_zs_next.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_OPENINGS_PARTIAL_PRODUCTS",
-        &*conf.num_openings_partial_products.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_OPENINGS_QUOTIENT_POLYS",
-        &*conf.num_openings_quotient_polys.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_COMMIT_ROUND",
-        &*conf.num_fri_commit_round.to_string(),
-    );
-    contract = contract.replace(
-        "$FRI_COMMIT_MERKLE_CAP_HEIGHT",
-        &*conf.fri_commit_merkle_cap_height.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_ROUND",
-        &*conf.num_fri_query_round.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_INIT_CONSTANTS_SIGMAS_V",
-        &*conf.num_fri_query_init_constants_sigmas_v.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_INIT_CONSTANTS_SIGMAS_P",
-        &*conf.num_fri_query_init_constants_sigmas_p.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_INIT_WIRES_V",
-        &*conf.num_fri_query_init_wires_v.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_INIT_WIRES_P",
-        &*conf.num_fri_query_init_wires_p.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_INIT_ZS_PARTIAL_V",
-        &*conf.num_fri_query_init_zs_partial_v.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_INIT_ZS_PARTIAL_P",
-        &*conf.num_fri_query_init_zs_partial_p.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_INIT_QUOTIENT_V",
-        &*conf.num_fri_query_init_quotient_v.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_INIT_QUOTIENT_P",
-        &*conf.num_fri_query_init_quotient_p.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_STEP0_V",
-        &*conf.num_fri_query_step0_v.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_STEP0_P",
-        &*conf.num_fri_query_step0_p.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_STEP1_V",
-        &*conf.num_fri_query_step1_v.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_QUERY_STEP1_P",
-        &*conf.num_fri_query_step1_p.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_FRI_FINAL_POLY_EXT_V",
-        &*conf.num_fri_final_poly_ext_v.to_string(),
-    );
-    contract = contract.replace(
-        "$NUM_CHALLENGES",
-        &*common.config.num_challenges.to_string(),
-    );
-
-    let circuit_digest = common.circuit_digest;
-    contract = contract.replace(
-        "$CIRCUIT_DIGEST",
-        &*("0x".to_owned() + &encode_hex(&circuit_digest.to_bytes())),
-    );
+    contract.set_statements(
+        "SET_REDUCTION_ARITY_BITS",
+        array_assignments("bits", reduction_arity_bits.iter().copied()),
+    )?;
+    contract.set("NUM_REDUCTION_ARITY_BITS", reduction_arity_bits.len())?;
+
+    for (placeholder, value) in [
+        ("NUM_WIRES_CAP", conf.num_wires_cap),
+        (
+            "NUM_PLONK_ZS_PARTIAL_PRODUCTS_CAP",
+            conf.num_plonk_zs_partial_products_cap,
+        ),
+        ("NUM_QUOTIENT_POLYS_CAP", conf.num_quotient_polys_cap),
+        ("NUM_OPENINGS_CONSTANTS", conf.num_openings_constants),
+        ("NUM_OPENINGS_PLONK_SIGMAS", conf.num_openings_plonk_sigmas),
+        ("NUM_OPENINGS_WIRES", conf.num_openings_wires),
+        ("NUM_OPENINGS_PLONK_ZS0", conf.num_openings_plonk_zs),
+        (
+            "NUM_OPENINGS_PLONK_ZS_NEXT",
+            conf.num_openings_plonk_zs_next,
+        ),
+        (
+            "NUM_OPENINGS_PARTIAL_PRODUCTS",
+            conf.num_openings_partial_products,
+        ),
+        (
+            "NUM_OPENINGS_QUOTIENT_POLYS",
+            conf.num_openings_quotient_polys,
+        ),
+        ("NUM_FRI_COMMIT_ROUND", conf.num_fri_commit_round),
+        (
+            "FRI_COMMIT_MERKLE_CAP_HEIGHT",
+            conf.fri_commit_merkle_cap_height,
+        ),
+        ("NUM_FRI_QUERY_ROUND", conf.num_fri_query_round),
+        (
+            "NUM_FRI_QUERY_INIT_CONSTANTS_SIGMAS_V",
+            conf.num_fri_query_init_constants_sigmas_v,
+        ),
+        (
+            "NUM_FRI_QUERY_INIT_WIRES_V",
+            conf.num_fri_query_init_wires_v,
+        ),
+        (
+            "NUM_FRI_QUERY_INIT_ZS_PARTIAL_V",
+            conf.num_fri_query_init_zs_partial_v,
+        ),
+        (
+            "NUM_FRI_QUERY_INIT_QUOTIENT_V",
+            conf.num_fri_query_init_quotient_v,
+        ),
+        ("NUM_FRI_FINAL_POLY_EXT_V", conf.num_fri_final_poly_ext_v),
+        ("NUM_CHALLENGES", common.config.num_challenges),
+        ("FRI_RATE_BITS", common.config.fri_config.rate_bits),
+        ("DEGREE_BITS", common.degree_bits),
+        ("NUM_GATE_CONSTRAINTS", common.num_gate_constraints),
+        ("QUOTIENT_DEGREE_FACTOR", common.quotient_degree_factor),
+        ("SALT_SIZE", conf.salt_size),
+    ] {
+        contract.set(placeholder, value)?;
+    }
 
-    contract = contract.replace(
-        "$FRI_RATE_BITS",
-        &*common.config.fri_config.rate_bits.to_string(),
-    );
-    contract = contract.replace("$DEGREE_BITS", &*common.degree_bits.to_string());
-    contract = contract.replace(
-        "$NUM_GATE_CONSTRAINTS",
-        &*common.num_gate_constraints.to_string(),
-    );
-    contract = contract.replace(
-        "$QUOTIENT_DEGREE_FACTOR",
-        &*common.quotient_degree_factor.to_string(),
-    );
-    contract = contract.replace(
-        "$MIN_FRI_POW_RESPONSE",
-        &*(common.config.fri_config.proof_of_work_bits + (64 - F::order().bits()) as u32)
-            .to_string(),
-    );
-    let g = F::Extension::primitive_root_of_unity(common.degree_bits);
-    contract = contract.replace(
-        "$G_FROM_DEGREE_BITS_0",
-        &g.to_basefield_array()[0].to_string(),
-    );
-    contract = contract.replace(
-        "$G_FROM_DEGREE_BITS_1",
-        &g.to_basefield_array()[1].to_string(),
-    );
+    contract.set(
+        "CIRCUIT_DIGEST",
+        Expr::Hex(common.circuit_digest.to_bytes()),
+    )?;
+    contract.set(
+        "MIN_FRI_POW_RESPONSE",
+        common.config.fri_config.proof_of_work_bits + (64 - F::order().bits()) as u32,
+    )?;
+    let g = F::Extension::primitive_root_of_unity(common.degree_bits).to_basefield_array();
+    contract.set("G_FROM_DEGREE_BITS_0", g[0].to_canonical_u64())?;
+    contract.set("G_FROM_DEGREE_BITS_1", g[1].to_canonical_u64())?;
     let log_n = log2_strict(common.fri_params.lde_size());
-    contract = contract.replace("$LOG_SIZE_OF_LDE_DOMAIN", &*log_n.to_string());
-    contract = contract.replace(
-        "$MULTIPLICATIVE_GROUP_GENERATOR",
-        &*F::MULTIPLICATIVE_GROUP_GENERATOR.to_string(),
-    );
-    contract = contract.replace(
-        "$PRIMITIVE_ROOT_OF_UNITY_LDE",
-        &*F::primitive_root_of_unity(log_n).to_string(),
-    );
-    // TODO: add test with config zero_knoledge = true
-    contract = contract.replace(
-        "$ZERO_KNOWLEDGE",
-        &*common.config.zero_knowledge.to_string(),
-    );
-    let g = F::primitive_root_of_unity(1);
-    contract = contract.replace("$G_ARITY_BITS_1", &g.to_string());
-    let g = F::primitive_root_of_unity(2);
-    contract = contract.replace("$G_ARITY_BITS_2", &g.to_string());
-    let g = F::primitive_root_of_unity(3);
-    contract = contract.replace("$G_ARITY_BITS_3", &g.to_string());
-    let g = F::primitive_root_of_unity(4);
-    contract = contract.replace("$G_ARITY_BITS_4", &g.to_string());
+    contract.set("LOG_SIZE_OF_LDE_DOMAIN", log_n)?;
+    contract.set(
+        "MULTIPLICATIVE_GROUP_GENERATOR",
+        F::MULTIPLICATIVE_GROUP_GENERATOR.to_canonical_u64(),
+    )?;
+    contract.set(
+        "PRIMITIVE_ROOT_OF_UNITY_LDE",
+        F::primitive_root_of_unity(log_n).to_canonical_u64(),
+    )?;
+    // Coset buffers and roots of unity are sized from the largest reduction arity.
+    let max_arity_bits = reduction_arity_bits.iter().copied().max().unwrap_or(1);
+    contract.set("MAX_ARITY_BITS", max_arity_bits)?;
+    contract.set("MAX_ARITY", 1usize << max_arity_bits)?;
+    contract.set_statements(
+        "SET_G_ARITY_BITS",
+        array_assignments(
+            "g_arity_bits",
+            (1..=max_arity_bits).map(|i| F::primitive_root_of_unity(i).to_canonical_u64()),
+        ),
+    )?;
 
     // Load gate template
-    let mut gates_lib = std::fs::read_to_string("./src/template_gates.sol")
-        .expect("Something went wrong reading the file");
+    let mut gates_lib_source = templates.gates.clone();
 
     let num_selectors = common.selectors_info.num_selectors();
-    contract = contract.replace("$NUM_SELECTORS", &num_selectors.to_string());
-    let mut evaluate_gate_constraints_str = "".to_owned();
+    contract.set("NUM_SELECTORS", num_selectors)?;
+    let mut evaluate_gate_constraints = Vec::new();
     for (row, gate) in common.gates.iter().enumerate() {
         if gate.0.id().eq("NoopGate") {
             continue;
         }
         let selector_index = common.selectors_info.selector_indices[row];
         let group_range = common.selectors_info.groups[selector_index].clone();
-        let mut c = 0;
 
-        evaluate_gate_constraints_str = evaluate_gate_constraints_str + "        {\n";
-        let mut filter_str = "ev.filter = ".to_owned();
-        let filter_chain = group_range
+        // The product of `i - selector` over the other gates of the group, and the unused
+        // selector value if there are several groups.
+        let filter = group_range
             .filter(|&i| i != row)
-            .chain((num_selectors > 1).then_some(u32::MAX as usize));
-        for i in filter_chain {
-            filter_str += &*("GatesUtilsLib.field_ext_from(".to_owned()
-                + &i.to_string()
-                + ", 0).sub("
-                + "ev.constants["
-                + &*selector_index.to_string()
-                + "]).mul(");
-            c = c + 1;
-        }
-        filter_str = filter_str[0..filter_str.len() - 5].parse()?;
-        for _ in 0..c - 1 {
-            filter_str = filter_str + ")";
-        }
-        filter_str = filter_str + ";";
+            .chain((num_selectors > 1).then_some(u32::MAX as usize))
+            .map(|i| {
+                Expr::ident("GatesUtilsLib")
+                    .method("field_ext_from", vec![i.into(), 0u32.into()])
+                    .method(
+                        "sub",
+                        vec![Expr::ident("ev").member("constants").index(selector_index)],
+                    )
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .reduce(|product, factor| factor.method("mul", vec![product]))
+            .unwrap_or_else(|| {
+                Expr::ident("GatesUtilsLib")
+                    .method("field_ext_from", vec![1u32.into(), 0u32.into()])
+            });
 
         // vars:
         //   proof.openings_constants
         //   proof.openings_wires
         //   challenges.public_input_hash
         // local_constants = local_constants[num_selectors..];
-        let mut eval_str = "            // ".to_owned() + &*gate.0.id() + "\n";
         let gate_name = gate.0.id();
-        if gate_name.eq("PublicInputGate")
-            || gate_name[0..11].eq("BaseSumGate")
-            || gate_name[0..12].eq("ConstantGate")
-            || gate_name[0..12].eq("ReducingGate")
-            || gate_name[0..14].eq("ArithmeticGate")
-            || gate_name[0..16].eq("MulExtensionGate")
-            || gate_name[0..16].eq("RandomAccessGate")
-            || gate_name[0..17].eq("U32ArithmeticGate")
-            || gate_name[0..18].eq("ExponentiationGate")
-            || gate_name[0..21].eq("ReducingExtensionGate")
-            || gate_name[0..23].eq("ArithmeticExtensionGate")
-            || gate_name[0..26].eq("LowDegreeInterpolationGate")
-        {
-            //TODO: use num_coeff as a param (same TODO for other gates)
-            let mut code_str = gate.0.export_solidity_verification_code();
-            code_str = code_str.replace("$SET_FILTER;", &*filter_str);
-            let v: Vec<&str> = code_str.split(' ').collect();
-            let lib_name = v[1];
-            eval_str += &*("            ".to_owned() + lib_name + ".set_filter(ev); \n");
-            eval_str +=
-                &*("            ".to_owned() + lib_name + ".eval(ev, vm.constraint_terms); \n");
-            gates_lib += &*(code_str + "\n");
-            // eval_str += &*format!("            console.log(\"{}\");", gate_name);
-            // eval_str += &*format!(
-            //     "
-            // for (uint32 i = 0; i < {}; i++) {{
-            //     console.log(i);
-            //     console.log(vm.constraint_terms[i][0]);
-            //     console.log(vm.constraint_terms[i][1]);
-            // }}
-            // console.log(\"\");\n",
-            //     &*common.num_gate_constraints.to_string(),
-            // );
-        } else if gate_name[0..12].eq("PoseidonGate") {
-            todo!("{}", "gate not implemented: ".to_owned() + &gate_name)
-        } else {
-            todo!("{}", "gate not implemented: ".to_owned() + &gate_name)
-        }
-        evaluate_gate_constraints_str += &*eval_str;
-        evaluate_gate_constraints_str += "        }\n";
+        let code = match gates.get(gate) {
+            Some(g) => g.export_solidity_verification_code(gate),
+            None => return Err(GeneratorError::UnsupportedGate(gate_name).into()),
+        };
+        let lib_name = match code.split_whitespace().nth(1) {
+            Some(lib_name) => lib_name.to_owned(),
+            None => return Err(GeneratorError::UnsupportedGate(gate_name).into()),
+        };
+        let mut gate_code = Template::new(&gate_name, &code);
+        gate_code.set_statements(
+            "SET_FILTER",
+            vec![Stmt::Assign(Expr::ident("ev").member("filter"), filter)],
+        )?;
+        gates_lib_source += &*(gate_code.render() + "\n");
+
+        let lib = Expr::ident(&lib_name);
+        evaluate_gate_constraints.push(Stmt::Block(vec![
+            Stmt::Comment(gate_name),
+            Stmt::Expr(lib.clone().method("set_filter", vec![Expr::ident("ev")])),
+            Stmt::Expr(lib.method(
+                "eval",
+                vec![
+                    Expr::ident("ev"),
+                    Expr::ident("vm").member("constraint_terms"),
+                ],
+            )),
+        ]));
+    }
+    contract.set_statements("EVALUATE_GATE_CONSTRAINTS", evaluate_gate_constraints)?;
+
+    let mut gates_lib = Template::new(TEMPLATE_GATES_FILE, &gates_lib_source);
+    gates_lib.set("NUM_GATE_CONSTRAINTS", common.num_gate_constraints)?;
+    gates_lib.set("NUM_OPENINGS_CONSTANTS", conf.num_openings_constants)?;
+    gates_lib.set("NUM_OPENINGS_WIRES", conf.num_openings_wires)?;
+    gates_lib.set("D", D)?;
+    gates_lib.set("F_EXT_W", F::W.to_basefield_array()[0].to_canonical_u64())?;
+    // Only used by the code of some gates.
+    if gates_lib.contains("NUM_SELECTORS") {
+        gates_lib.set("NUM_SELECTORS", num_selectors)?;
     }
-    contract = contract.replace(
-        "        $EVALUATE_GATE_CONSTRAINTS;",
-        &evaluate_gate_constraints_str[0..evaluate_gate_constraints_str.len() - 1],
-    );
-
-    gates_lib = gates_lib.replace(
-        "$NUM_GATE_CONSTRAINTS",
-        &*common.num_gate_constraints.to_string(),
-    );
-    gates_lib = gates_lib.replace("$NUM_SELECTORS", &num_selectors.to_string());
-    gates_lib = gates_lib.replace(
-        "$NUM_OPENINGS_CONSTANTS",
-        &*conf.num_openings_constants.to_string(),
-    );
-    gates_lib = gates_lib.replace("$NUM_OPENINGS_WIRES", &*conf.num_openings_wires.to_string());
-    gates_lib = gates_lib.replace("$D", &*D.to_string());
-    gates_lib = gates_lib.replace("$F_EXT_W", &*F::W.to_basefield_array()[0].to_string());
 
     // Load proof template
-    let mut proof_lib = std::fs::read_to_string("./src/template_proof.sol")
-        .expect("Something went wrong reading the file");
+    let mut proof_lib = Template::new(TEMPLATE_PROOF_FILE, &templates.proof);
 
     let sigma_cap_count = 1 << common.config.fri_config.cap_height;
-    proof_lib = proof_lib.replace("$SIGMA_CAP_COUNT", &*sigma_cap_count.to_string());
-
-    let mut sigma_cap_str = "".to_owned();
-    for i in 0..sigma_cap_count {
-        let cap = verifier_only.constants_sigmas_cap.0[i];
-        let hash = encode_hex(&cap.to_bytes());
-        sigma_cap_str += &*("        sc[".to_owned() + &*i.to_string() + "] = 0x" + &*hash + ";\n");
+    proof_lib.set("SIGMA_CAP_COUNT", sigma_cap_count)?;
+    proof_lib.set_statements(
+        "SET_SIGMA_CAP",
+        array_assignments(
+            "sc",
+            verifier_only.constants_sigmas_cap.0[..sigma_cap_count]
+                .iter()
+                .map(|cap| Expr::Hex(cap.to_bytes())),
+        ),
+    )?;
+
+    let layout = ProofLayout::new(conf);
+    for (placeholder, ptr) in [
+        (
+            "PLONK_ZS_PARTIAL_PRODUCTS_CAP_PTR",
+            layout.plonk_zs_partial_products_cap_ptr,
+        ),
+        ("QUOTIENT_POLYS_CAP_PTR", layout.quotient_polys_cap_ptr),
+        ("OPENINGS_CONSTANTS_PTR", layout.openings_constants_ptr),
+        (
+            "OPENINGS_PLONK_SIGMAS_PTR",
+            layout.openings_plonk_sigmas_ptr,
+        ),
+        ("OPENINGS_WIRES_PTR", layout.openings_wires_ptr),
+        ("OPENINGS_PLONK_ZS_PTR", layout.openings_plonk_zs_ptr),
+        (
+            "OPENINGS_PLONK_ZS_NEXT_PTR",
+            layout.openings_plonk_zs_next_ptr,
+        ),
+        (
+            "OPENINGS_PARTIAL_PRODUCTS_PTR",
+            layout.openings_partial_products_ptr,
+        ),
+        (
+            "OPENINGS_QUOTIENT_POLYS_PTR",
+            layout.openings_quotient_polys_ptr,
+        ),
+        (
+            "FRI_COMMIT_PHASE_MERKLE_CAPS_PTR",
+            layout.fri_commit_phase_merkle_caps_ptr,
+        ),
+        ("FRI_COMMIT_ROUND_SIZE", layout.fri_commit_round_size),
+        ("FRI_QUERY_ROUND_PTR", layout.fri_query_round_ptr),
+        ("FRI_QUERY_ROUND_SIZE", layout.fri_query_round_size),
+        (
+            "INIT_CONSTANTS_SIGMAS_P_PTR",
+            layout.init_constants_sigmas_p_ptr,
+        ),
+        ("INIT_WIRES_V_PTR", layout.init_wires_v_ptr),
+        ("INIT_WIRES_P_PTR", layout.init_wires_p_ptr),
+        ("INIT_ZS_PARTIAL_V_PTR", layout.init_zs_partial_v_ptr),
+        ("INIT_ZS_PARTIAL_P_PTR", layout.init_zs_partial_p_ptr),
+        ("INIT_QUOTIENT_V_PTR", layout.init_quotient_v_ptr),
+        ("INIT_QUOTIENT_P_PTR", layout.init_quotient_p_ptr),
+        ("FRI_FINAL_POLY_EXT_V_PTR", layout.fri_final_poly_ext_v_ptr),
+        ("FRI_POW_WITNESS_PTR", layout.fri_pow_witness_ptr),
+        ("PUBLIC_INPUTS_PTR", layout.public_inputs_ptr),
+        (
+            "NUM_FRI_QUERY_INIT_CONSTANTS_SIGMAS_P",
+            conf.num_fri_query_init_constants_sigmas_p,
+        ),
+        (
+            "NUM_FRI_QUERY_INIT_WIRES_P",
+            conf.num_fri_query_init_wires_p,
+        ),
+        (
+            "NUM_FRI_QUERY_INIT_ZS_PARTIAL_P",
+            conf.num_fri_query_init_zs_partial_p,
+        ),
+        (
+            "NUM_FRI_QUERY_INIT_QUOTIENT_P",
+            conf.num_fri_query_init_quotient_p,
+        ),
+        ("NUM_PUBLIC_INPUTS", conf.num_public_inputs),
+    ] {
+        proof_lib.set(placeholder, ptr)?;
     }
-    proof_lib = proof_lib.replace("        $SET_SIGMA_CAP;\n", &*sigma_cap_str);
-
-    proof_lib = proof_lib.replace(
-        "$PLONK_ZS_PARTIAL_PRODUCTS_CAP_PTR",
-        &*(conf.num_wires_cap * conf.hash_size).to_string(),
-    );
-    proof_lib = proof_lib.replace(
-        "$QUOTIENT_POLYS_CAP_PTR",
-        &*((conf.num_wires_cap + conf.num_plonk_zs_partial_products_cap) * conf.hash_size)
-            .to_string(),
-    );
-
-    let mut proof_size: usize =
-        (conf.num_wires_cap + conf.num_plonk_zs_partial_products_cap + conf.num_quotient_polys_cap)
-            * conf.hash_size;
-    proof_lib = proof_lib.replace("$OPENINGS_CONSTANTS_PTR", &*proof_size.to_string());
-
-    proof_size += conf.num_openings_constants * conf.ext_field_size;
-    proof_lib = proof_lib.replace("$OPENINGS_PLONK_SIGMAS_PTR", &*proof_size.to_string());
-
-    proof_size += conf.num_openings_plonk_sigmas * conf.ext_field_size;
-    proof_lib = proof_lib.replace("$OPENINGS_WIRES_PTR", &*proof_size.to_string());
-
-    proof_size += conf.num_openings_wires * conf.ext_field_size;
-    proof_lib = proof_lib.replace("$OPENINGS_PLONK_ZS_PTR", &*proof_size.to_string());
-
-    proof_size += conf.num_openings_plonk_zs * conf.ext_field_size;
-    proof_lib = proof_lib.replace("$OPENINGS_PLONK_ZS_NEXT_PTR", &*proof_size.to_string());
-
-    proof_size += conf.num_openings_plonk_zs_next * conf.ext_field_size;
-    proof_lib = proof_lib.replace("$OPENINGS_PARTIAL_PRODUCTS_PTR", &*proof_size.to_string());
-
-    proof_size += conf.num_openings_partial_products * conf.ext_field_size;
-    proof_lib = proof_lib.replace("$OPENINGS_QUOTIENT_POLYS_PTR", &*proof_size.to_string());
-
-    proof_size += conf.num_openings_quotient_polys * conf.ext_field_size;
-
-    proof_lib = proof_lib.replace(
-        "$FRI_COMMIT_PHASE_MERKLE_CAPS_PTR",
-        &*proof_size.to_string(),
-    );
-    proof_lib = proof_lib.replace(
-        "$FRI_COMMIT_ROUND_SIZE",
-        &*(conf.fri_commit_merkle_cap_height * conf.hash_size).to_string(),
-    );
-    proof_size += (conf.num_fri_commit_round * conf.fri_commit_merkle_cap_height) * conf.hash_size;
-
-    let fri_query_round_ptr = proof_size;
-    proof_lib = proof_lib.replace("$FRI_QUERY_ROUND_PTR", &*fri_query_round_ptr.to_string());
-
-    let fri_query_round_size = (conf.num_fri_query_init_constants_sigmas_v
-        + conf.num_fri_query_init_wires_v
-        + conf.num_fri_query_init_zs_partial_v
-        + conf.num_fri_query_init_quotient_v)
-        * conf.field_size
-        + (conf.num_fri_query_init_constants_sigmas_p
-            + conf.num_fri_query_init_wires_p
-            + conf.num_fri_query_init_zs_partial_p
-            + conf.num_fri_query_init_quotient_p)
-            * conf.hash_size
-        + conf.merkle_height_size * 4
-        + conf.num_fri_query_step0_v * conf.ext_field_size
-        + conf.num_fri_query_step0_p * conf.hash_size
-        + conf.merkle_height_size
-        + conf.num_fri_query_step1_v * conf.ext_field_size
-        + conf.num_fri_query_step1_p * conf.hash_size
-        + conf.merkle_height_size;
-    proof_lib = proof_lib.replace("$FRI_QUERY_ROUND_SIZE", &*fri_query_round_size.to_string());
-
-    let mut round_ptr = conf.num_fri_query_init_constants_sigmas_v * conf.field_size + 1;
-    proof_lib = proof_lib.replace("$INIT_CONSTANTS_SIGMAS_P_PTR", &*round_ptr.to_string());
-    round_ptr += conf.num_fri_query_init_constants_sigmas_p * conf.hash_size;
-    proof_lib = proof_lib.replace("$INIT_WIRES_V_PTR", &*round_ptr.to_string());
-
-    round_ptr += conf.num_fri_query_init_wires_v * conf.field_size + 1;
-    proof_lib = proof_lib.replace("$INIT_WIRES_P_PTR", &*round_ptr.to_string());
-    round_ptr += conf.num_fri_query_init_wires_p * conf.hash_size;
-    proof_lib = proof_lib.replace("$INIT_ZS_PARTIAL_V_PTR", &*round_ptr.to_string());
-
-    round_ptr += conf.num_fri_query_init_zs_partial_v * conf.field_size + 1;
-    proof_lib = proof_lib.replace("$INIT_ZS_PARTIAL_P_PTR", &*round_ptr.to_string());
-    round_ptr += conf.num_fri_query_init_zs_partial_p * conf.hash_size;
-    proof_lib = proof_lib.replace("$INIT_QUOTIENT_V_PTR", &*round_ptr.to_string());
-
-    round_ptr += conf.num_fri_query_init_quotient_v * conf.field_size + 1;
-    proof_lib = proof_lib.replace("$INIT_QUOTIENT_P_PTR", &*round_ptr.to_string());
-    round_ptr += conf.num_fri_query_init_quotient_p * conf.hash_size;
-    proof_lib = proof_lib.replace("$STEP0_V_PTR", &*round_ptr.to_string());
-
-    round_ptr += conf.num_fri_query_step0_v * conf.ext_field_size + 1;
-    proof_lib = proof_lib.replace("$STEP0_P_PTR", &*round_ptr.to_string());
-    round_ptr += conf.num_fri_query_step0_p * conf.hash_size;
-    proof_lib = proof_lib.replace("$STEP1_V_PTR", &*round_ptr.to_string());
-
-    round_ptr += conf.num_fri_query_step1_v * conf.ext_field_size + 1;
-    proof_lib = proof_lib.replace("$STEP1_P_PTR", &*round_ptr.to_string());
-    round_ptr += conf.num_fri_query_step1_p * conf.hash_size;
-    assert_eq!(round_ptr, fri_query_round_size);
-
-    proof_size += fri_query_round_size * conf.num_fri_query_round;
-    proof_lib = proof_lib.replace("$FRI_FINAL_POLY_EXT_V_PTR", &*proof_size.to_string());
-
-    proof_size += conf.ext_field_size * conf.num_fri_final_poly_ext_v;
-    proof_lib = proof_lib.replace("$FRI_POW_WITNESS_PTR", &*proof_size.to_string());
-
-    proof_size += conf.field_size;
-    proof_lib = proof_lib.replace("$PUBLIC_INPUTS_PTR", &*proof_size.to_string());
-
-    proof_lib = proof_lib.replace(
-        "$NUM_FRI_QUERY_INIT_CONSTANTS_SIGMAS_P",
-        &*conf.num_fri_query_init_constants_sigmas_p.to_string(),
-    );
-    proof_lib = proof_lib.replace(
-        "$NUM_FRI_QUERY_INIT_WIRES_P",
-        &*conf.num_fri_query_init_wires_p.to_string(),
-    );
-    proof_lib = proof_lib.replace(
-        "$NUM_FRI_QUERY_INIT_ZS_PARTIAL_P",
-        &*conf.num_fri_query_init_zs_partial_p.to_string(),
-    );
-    proof_lib = proof_lib.replace(
-        "$NUM_FRI_QUERY_INIT_QUOTIENT_P",
-        &*conf.num_fri_query_init_quotient_p.to_string(),
-    );
-    proof_lib = proof_lib.replace(
-        "$NUM_FRI_QUERY_STEP0_P",
-        &*conf.num_fri_query_step0_p.to_string(),
-    );
-    proof_lib = proof_lib.replace(
-        "$NUM_FRI_QUERY_STEP1_P",
-        &*conf.num_fri_query_step1_p.to_string(),
-    );
-    proof_lib = proof_lib.replace("$NUM_PUBLIC_INPUTS", &*conf.num_public_inputs.to_string());
 
-    Ok((contract, gates_lib, proof_lib))
+    // `if (s == i) return values[i];` for each step, then revert.
+    let step_lookup = |values: &[usize]| {
+        let mut statements = values
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                Stmt::If(
+                    Expr::ident("s").binary("==", i),
+                    Box::new(Stmt::Return(v.into())),
+                )
+            })
+            .collect::<Vec<_>>();
+        statements.push(Stmt::Revert);
+        statements
+    };
+    proof_lib.set_statements(
+        "GET_FRI_QUERY_STEP_V_PTR",
+        step_lookup(&layout.fri_query_step_v_ptrs[..]),
+    )?;
+    proof_lib.set_statements(
+        "GET_FRI_QUERY_STEP_P_PTR",
+        step_lookup(&layout.fri_query_step_p_ptrs[..]),
+    )?;
+    proof_lib.set_statements(
+        "GET_NUM_FRI_QUERY_STEP_P",
+        step_lookup(&conf.num_fri_query_step_p[..]),
+    )?;
+
+    Ok((
+        contract.render_checked()?,
+        gates_lib.render_checked()?,
+        proof_lib.render_checked()?,
+    ))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs::File;
-    use std::io::Write;
-    use std::path::Path;
-
     use anyhow::Result;
-    use plonky2::field::extension::Extendable;
+    use plonky2::field::types::Field;
+    use plonky2::fri::oracle::SALT_SIZE;
     use plonky2::fri::reduction_strategies::FriReductionStrategy;
     use plonky2::fri::FriConfig;
-    use plonky2::hash::hash_types::RichField;
+    use plonky2::hash::hashing::SPONGE_WIDTH;
+    use plonky2::hash::poseidon::{Poseidon, PoseidonHash};
     use plonky2::iop::witness::Witness;
-    use plonky2::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
-    use plonky2::plonk::config::Hasher;
-    use plonky2::plonk::proof::ProofWithPublicInputs;
     use plonky2::{
-        gates::noop::NoopGate,
         iop::witness::PartialWitness,
         plonk::{
             circuit_builder::CircuitBuilder,
@@ -748,222 +903,441 @@ mod tests {
     };
 
     use crate::config::KeccakGoldilocksConfig2;
+    use crate::error::GeneratorError;
+    use crate::gates::GateRegistry;
+    use crate::project::{write_verifier_project, VerifierProject};
+    use crate::solidity::placeholders;
+    use crate::templates::{SolidityTemplates, TEMPLATE_PROOF_FILE};
+    use crate::test_utils::dummy_proof;
     use crate::verifier::{
-        generate_proof_base64, generate_solidity_verifier, generate_verifier_config,
-        recursive_proof,
+        generate_solidity_verifier, generate_solidity_verifier_with_gates,
+        generate_solidity_verifier_with_templates, generate_verifier_config,
+        generate_verifier_config_from_common_data, recursive_proof, VerifierConfig,
     };
+    use crate::wrap::{final_config, high_rate_config, wrap_for_evm, WrappedProof};
 
-    /// Creates a dummy proof which should have roughly `num_dummy_gates` gates.
-    fn dummy_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
-        config: &CircuitConfig,
-        num_dummy_gates: u64,
-        num_public_inputs: u64,
-    ) -> Result<(
-        ProofWithPublicInputs<F, C, D>,
-        VerifierOnlyCircuitData<C, D>,
-        CommonCircuitData<F, C, D>,
-    )>
-    where
-        [(); C::Hasher::HASH_SIZE]:,
-    {
-        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
-        for _ in 0..num_dummy_gates {
-            builder.add_gate(NoopGate, vec![]);
-        }
-        let mut pi = Vec::new();
-        if num_public_inputs > 0 {
-            pi = builder.add_virtual_targets(num_public_inputs as usize);
-            builder.register_public_inputs(&pi);
-        }
+    #[test]
+    fn test_verifier_without_public_inputs() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+        let final_config = final_config();
 
-        let data = builder.build::<C>();
-        let mut inputs = PartialWitness::new();
-        if num_public_inputs > 0 {
-            for i in 0..num_public_inputs {
-                inputs.set_target(pi[i as usize], F::from_canonical_u64(i));
-            }
-        }
-        let proof = data.prove(inputs)?;
-        data.verify(proof.clone())?;
+        let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config, 4_000, 0)?;
 
-        Ok((proof, data.verifier_only, data.common))
+        let conf = generate_verifier_config(&proof)?;
+        write_verifier_project("./contract", &conf, &cd, &vd, &proof)?;
+
+        Ok(())
     }
 
     #[test]
-    fn test_verifier_without_public_inputs() -> Result<()> {
+    fn test_verifier_with_public_inputs() -> Result<()> {
         const D: usize = 2;
         type KC2 = KeccakGoldilocksConfig2;
         type F = <KC2 as GenericConfig<D>>::F;
-        let standard_config = CircuitConfig::standard_recursion_config();
-        // A high-rate recursive proof, designed to be verifiable with fewer routed wires.
-        let high_rate_config = CircuitConfig {
-            fri_config: FriConfig {
-                rate_bits: 7,
-                proof_of_work_bits: 16,
-                num_query_rounds: 12,
-                ..standard_config.fri_config.clone()
-            },
-            ..standard_config
+        let final_config = CircuitConfig {
+            num_routed_wires: 65,
+            ..final_config()
         };
-        // A final proof, optimized for size.
+
+        let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config, 4_000, 4)?;
+
+        let conf = generate_verifier_config(&proof)?;
+        write_verifier_project("./contract", &conf, &cd, &vd, &proof)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verifier_with_three_fri_steps() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+        let standard_config = CircuitConfig::standard_recursion_config();
         let final_config = CircuitConfig {
             num_routed_wires: 37,
             fri_config: FriConfig {
                 rate_bits: 8,
                 cap_height: 0,
                 proof_of_work_bits: 20,
-                reduction_strategy: FriReductionStrategy::MinSize(None),
+                reduction_strategy: FriReductionStrategy::ConstantArityBits(3, 5),
                 num_query_rounds: 10,
             },
-            ..high_rate_config
+            ..standard_config
         };
 
         let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config, 4_000, 0)?;
+        assert_eq!(cd.fri_params.reduction_arity_bits, vec![3, 3, 3]);
 
         let conf = generate_verifier_config(&proof)?;
-        let (contract, gates_lib, proof_lib) = generate_solidity_verifier(&conf, &cd, &vd)?;
-
-        let mut sol_file = File::create("./contract/contracts/Verifier.sol")?;
-        sol_file.write_all(contract.as_bytes())?;
-        sol_file = File::create("./contract/contracts/GatesLib.sol")?;
-        sol_file.write_all(gates_lib.as_bytes())?;
-        sol_file = File::create("./contract/contracts/ProofLib.sol")?;
-        sol_file.write_all(proof_lib.as_bytes())?;
+        assert_eq!(conf, generate_verifier_config_from_common_data(&cd)?);
+        let project = VerifierProject::generate(&conf, &cd, &vd, &proof)?;
+        assert!(project.proof_lib.contains("        if (s == 2) return"));
+        project.write("./contract")?;
 
-        let proof_base64 = generate_proof_base64(&proof, &conf)?;
-        let proof_json = "[ \"".to_owned() + &proof_base64 + &"\" ]";
+        Ok(())
+    }
 
-        if !Path::new("./contract/test/data").is_dir() {
-            std::fs::create_dir("./contract/test/data")?;
-        }
+    #[test]
+    fn test_verifier_with_arity_bits_5() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+        let standard_config = CircuitConfig::standard_recursion_config();
+        let final_config = CircuitConfig {
+            num_routed_wires: 37,
+            fri_config: FriConfig {
+                rate_bits: 8,
+                cap_height: 0,
+                proof_of_work_bits: 20,
+                reduction_strategy: FriReductionStrategy::ConstantArityBits(5, 2),
+                num_query_rounds: 10,
+            },
+            ..standard_config
+        };
 
-        let mut proof_file = File::create("./contract/test/data/proof.json")?;
-        proof_file.write_all(proof_json.as_bytes())?;
+        let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config, 4_000, 0)?;
+        assert_eq!(cd.fri_params.reduction_arity_bits, vec![5, 5]);
 
-        let mut conf_file = File::create("./contract/test/data/conf.json")?;
-        conf_file.write_all(serde_json::to_string(&conf)?.as_ref())?;
+        let conf = generate_verifier_config(&proof)?;
+        let project = VerifierProject::generate(&conf, &cd, &vd, &proof)?;
+        assert!(project
+            .contract
+            .contains("uint32 constant MAX_ARITY_BITS = 5;"));
+        assert!(project.contract.contains("        g_arity_bits[4] = "));
+        project.write("./contract")?;
 
         Ok(())
     }
 
     #[test]
-    fn test_verifier_with_public_inputs() -> Result<()> {
+    fn test_verifier_with_one_fri_step() -> Result<()> {
         const D: usize = 2;
         type KC2 = KeccakGoldilocksConfig2;
         type F = <KC2 as GenericConfig<D>>::F;
         let standard_config = CircuitConfig::standard_recursion_config();
-        // A high-rate recursive proof, designed to be verifiable with fewer routed wires.
-        let high_rate_config = CircuitConfig {
+        let final_config = CircuitConfig {
+            num_routed_wires: 37,
             fri_config: FriConfig {
-                rate_bits: 7,
-                proof_of_work_bits: 16,
-                num_query_rounds: 12,
-                ..standard_config.fri_config.clone()
+                rate_bits: 8,
+                cap_height: 0,
+                proof_of_work_bits: 20,
+                reduction_strategy: FriReductionStrategy::Fixed(vec![4]),
+                num_query_rounds: 10,
             },
             ..standard_config
         };
-        // A final proof, optimized for size.
+
+        let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config, 4_000, 0)?;
+        assert_eq!(cd.fri_params.reduction_arity_bits, vec![4]);
+
+        let conf = generate_verifier_config(&proof)?;
+        assert_eq!(conf, generate_verifier_config_from_common_data(&cd)?);
+        let project = VerifierProject::generate(&conf, &cd, &vd, &proof)?;
+        assert!(project.proof_lib.contains("        if (s == 0) return"));
+        assert!(!project.proof_lib.contains("        if (s == 1) return"));
+        assert!(project
+            .contract
+            .contains("uint32 constant MAX_ARITY_BITS = 4;"));
+        project.write("./contract")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verifier_without_fri_steps() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+        let standard_config = CircuitConfig::standard_recursion_config();
         let final_config = CircuitConfig {
-            num_routed_wires: 65,
+            num_routed_wires: 37,
             fri_config: FriConfig {
                 rate_bits: 8,
                 cap_height: 0,
                 proof_of_work_bits: 20,
-                reduction_strategy: FriReductionStrategy::MinSize(None),
+                reduction_strategy: FriReductionStrategy::Fixed(vec![]),
                 num_query_rounds: 10,
             },
-            ..high_rate_config
+            ..standard_config
+        };
+
+        // The verifier would declare zero-length arrays, which solc rejects.
+        let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config, 100, 0)?;
+        assert!(cd.fri_params.reduction_arity_bits.is_empty());
+        let conf = generate_verifier_config(&proof)?;
+        let err = generate_solidity_verifier(&conf, &cd, &vd).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<GeneratorError>(),
+            Some(GeneratorError::UnsupportedFriShape(_))
+        ));
+        assert!(generate_verifier_config_from_common_data(&cd).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verifier_with_cap_height() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+        let final_config = final_config();
+        let final_config = CircuitConfig {
+            fri_config: FriConfig {
+                cap_height: 2,
+                ..final_config.fri_config.clone()
+            },
+            ..final_config
         };
 
         let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config, 4_000, 4)?;
 
         let conf = generate_verifier_config(&proof)?;
-        let (contract, gates_lib, proof_lib) = generate_solidity_verifier(&conf, &cd, &vd)?;
+        assert_eq!(conf, generate_verifier_config_from_common_data(&cd)?);
+        assert_eq!(conf.num_wires_cap, 4);
+        assert_eq!(conf.fri_commit_merkle_cap_height, 4);
+        let project = VerifierProject::generate(&conf, &cd, &vd, &proof)?;
+        assert!(project.proof_lib.contains("        sc[3] = 0x"));
+        project.write("./contract")?;
 
-        let mut sol_file = File::create("./contract/contracts/Verifier.sol")?;
-        sol_file.write_all(contract.as_bytes())?;
-        sol_file = File::create("./contract/contracts/GatesLib.sol")?;
-        sol_file.write_all(gates_lib.as_bytes())?;
-        sol_file = File::create("./contract/contracts/ProofLib.sol")?;
-        sol_file.write_all(proof_lib.as_bytes())?;
-
-        let proof_base64 = generate_proof_base64(&proof, &conf)?;
-        let proof_json = "[ \"".to_owned() + &proof_base64 + &"\" ]";
+        Ok(())
+    }
 
-        if !Path::new("./contract/test/data").is_dir() {
-            std::fs::create_dir("./contract/test/data")?;
-        }
+    #[test]
+    fn test_verifier_with_zero_knowledge() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+        let final_config = CircuitConfig {
+            zero_knowledge: true,
+            ..final_config()
+        };
 
-        let mut proof_file = File::create("./contract/test/data/proof.json")?;
-        proof_file.write_all(proof_json.as_bytes())?;
+        let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config, 4_000, 4)?;
 
-        let mut conf_file = File::create("./contract/test/data/conf.json")?;
-        conf_file.write_all(serde_json::to_string(&conf)?.as_ref())?;
+        let conf = generate_verifier_config(&proof)?;
+        assert_eq!(conf, generate_verifier_config_from_common_data(&cd)?);
+        assert_eq!(conf.salt_size, SALT_SIZE);
+        let project = VerifierProject::generate(&conf, &cd, &vd, &proof)?;
+        assert!(project.contract.contains("uint32 constant SALT_SIZE = 4;"));
+        project.write("./contract")?;
 
         Ok(())
     }
 
     #[test]
-    fn test_recursive_verifier_without_public_inputs() -> Result<()> {
+    fn test_unsupported_circuits() -> Result<()> {
         const D: usize = 2;
         type C = PoseidonGoldilocksConfig;
-        type F = <C as GenericConfig<D>>::F;
         type KC2 = KeccakGoldilocksConfig2;
-        let standard_config = CircuitConfig::standard_recursion_config();
+        type F = <KC2 as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
 
-        let (proof, vd, cd) = dummy_proof::<F, C, D>(&standard_config, 4_000, 0)?;
+        let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&config, 100, 0)?;
+        let conf = generate_verifier_config(&proof)?;
+        let err = generate_solidity_verifier_with_gates(&conf, &cd, &vd, &GateRegistry::new())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<GeneratorError>(),
+            Some(GeneratorError::UnsupportedGate(_))
+        ));
+
+        let (proof, vd, cd) = dummy_proof::<F, C, D>(&config, 100, 0)?;
+        let conf = generate_verifier_config(&proof)?;
+        let err = generate_solidity_verifier(&conf, &cd, &vd).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<GeneratorError>(),
+            Some(GeneratorError::UnsupportedHasher { hash_size: 32 })
+        ));
 
-        // A high-rate recursive proof, designed to be verifiable with fewer routed wires.
-        let high_rate_config = CircuitConfig {
-            fri_config: FriConfig {
-                rate_bits: 7,
-                proof_of_work_bits: 16,
-                num_query_rounds: 12,
-                ..standard_config.fri_config.clone()
-            },
-            ..standard_config
-        };
+        Ok(())
+    }
 
-        let (proof, vd, cd) =
-            recursive_proof::<F, C, C, D>(proof, vd, cd, &high_rate_config, None, true, true)?;
+    #[test]
+    fn test_template_placeholders_consumed() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
 
-        // A final proof, optimized for size.
+        let (proof, vd, cd) = dummy_proof::<F, KC2, D>(&final_config(), 4_000, 2)?;
+        let conf = generate_verifier_config(&proof)?;
+        let outputs = generate_solidity_verifier(&conf, &cd, &vd)?;
+        for output in [&outputs.0, &outputs.1, &outputs.2] {
+            assert!(placeholders(output).is_empty());
+        }
+
+        let mut templates = SolidityTemplates::default();
+        templates.proof = templates.proof.replacen(
+            "library ProofLib {",
+            "library ProofLib {\n    uint32 constant NUM_PROOFS = $NUM_PROOFS;",
+            1,
+        );
+        let err = generate_solidity_verifier_with_templates(
+            &conf,
+            &cd,
+            &vd,
+            &GateRegistry::default(),
+            &templates,
+        )
+        .unwrap_err();
+        match err.downcast_ref::<GeneratorError>() {
+            Some(GeneratorError::UnreplacedPlaceholders {
+                template,
+                placeholders,
+            }) => {
+                assert_eq!(template, TEMPLATE_PROOF_FILE);
+                assert_eq!(placeholders, &["NUM_PROOFS"]);
+            }
+            _ => panic!("unexpected error: {}", err),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verifier_config_from_common_data() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type KC2 = KeccakGoldilocksConfig2;
+        let standard_config = CircuitConfig::standard_recursion_config();
+        let high_rate_config = high_rate_config();
         let final_config = CircuitConfig {
-            num_routed_wires: 37,
-            fri_config: FriConfig {
-                rate_bits: 8,
-                cap_height: 0,
-                proof_of_work_bits: 20,
-                reduction_strategy: FriReductionStrategy::MinSize(None),
-                num_query_rounds: 10,
-            },
-            ..high_rate_config
+            num_routed_wires: 65,
+            ..final_config()
         };
+
+        let (proof, _, cd) = dummy_proof::<F, KC2, D>(&final_config, 4_000, 4)?;
+        assert_eq!(
+            generate_verifier_config_from_common_data(&cd)?,
+            generate_verifier_config(&proof)?
+        );
+
+        let (proof, vd, cd) = dummy_proof::<F, C, D>(&standard_config, 4_000, 0)?;
         let (proof, vd, cd) =
-            recursive_proof::<F, KC2, C, D>(proof, vd, cd, &final_config, None, true, true)?;
+            recursive_proof::<F, C, C, D>(proof, vd, cd, &high_rate_config, None, false, false)?;
+        let (proof, _, cd) =
+            recursive_proof::<F, KC2, C, D>(proof, vd, cd, &final_config, None, false, false)?;
+        assert_eq!(
+            generate_verifier_config_from_common_data(&cd)?,
+            generate_verifier_config(&proof)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verifier_config_json() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
 
+        let (proof, _, cd) = dummy_proof::<F, KC2, D>(&final_config(), 4_000, 4)?;
         let conf = generate_verifier_config(&proof)?;
-        let (contract, gates_lib, proof_lib) = generate_solidity_verifier(&conf, &cd, &vd)?;
+        let loaded: VerifierConfig = serde_json::from_str(&serde_json::to_string(&conf)?)?;
+        assert_eq!(loaded, conf);
+        assert_eq!(loaded.num_public_inputs(), 4);
+        assert_eq!(
+            loaded.num_fri_query_step_v().len(),
+            cd.fri_params.reduction_arity_bits.len()
+        );
+        loaded.validate(&cd)?;
+
+        let (_, _, other_cd) = dummy_proof::<F, KC2, D>(&final_config(), 4_000, 2)?;
+        let err = loaded.validate(&other_cd).unwrap_err();
+        match err.downcast_ref::<GeneratorError>() {
+            Some(GeneratorError::ConfigMismatch {
+                field,
+                expected,
+                actual,
+            }) => {
+                assert_eq!(field, "num_public_inputs");
+                assert_eq!(expected, "2");
+                assert_eq!(actual, "4");
+            }
+            _ => panic!("unexpected error: {}", err),
+        }
 
-        let mut sol_file = File::create("./contract/contracts/Verifier.sol")?;
-        sol_file.write_all(contract.as_bytes())?;
-        sol_file = File::create("./contract/contracts/GatesLib.sol")?;
-        sol_file.write_all(gates_lib.as_bytes())?;
-        sol_file = File::create("./contract/contracts/ProofLib.sol")?;
-        sol_file.write_all(proof_lib.as_bytes())?;
+        loaded.check()?;
+        let mut invalid = loaded.clone();
+        invalid.field_size = 4;
+        assert!(matches!(
+            invalid.check(),
+            Err(GeneratorError::ConfigMismatch { field, .. }) if field == "field_size"
+        ));
+        assert!(invalid.validate(&cd).is_err());
+        let mut invalid = loaded;
+        invalid.num_fri_query_step_p.pop();
+        assert!(invalid.check().is_err());
 
-        let proof_base64 = generate_proof_base64(&proof, &conf)?;
-        let proof_json = "[ \"".to_owned() + &proof_base64 + &"\" ]";
+        Ok(())
+    }
+
+    #[test]
+    fn test_verifier_with_poseidon_gadgets() -> Result<()> {
+        const D: usize = 2;
+        type KC2 = KeccakGoldilocksConfig2;
+        type F = <KC2 as GenericConfig<D>>::F;
+        // Keeps enough routed wires for PoseidonMdsGate.
+        let final_config = CircuitConfig {
+            num_routed_wires: 65,
+            ..final_config()
+        };
 
-        if !Path::new("./contract/test/data").is_dir() {
-            std::fs::create_dir("./contract/test/data")?;
+        let mut builder = CircuitBuilder::<F, D>::new(final_config);
+        let inputs = builder.add_virtual_targets(8);
+        // PoseidonGate
+        builder.hash_n_to_hash_no_pad::<PoseidonHash>(inputs.clone());
+        // PoseidonMdsGate
+        let state = core::array::from_fn::<_, SPONGE_WIDTH, _>(|i| {
+            builder.convert_to_ext(inputs[i % inputs.len()])
+        });
+        <F as Poseidon>::mds_layer_circuit(&mut builder, &state);
+
+        let data = builder.build::<KC2>();
+        let mut pw = PartialWitness::new();
+        for i in 0..inputs.len() {
+            pw.set_target(inputs[i], F::from_canonical_u64(i as u64));
         }
+        let proof = data.prove(pw)?;
+        data.verify(proof.clone())?;
+        let (vd, cd) = (data.verifier_only, data.common);
+
+        let conf = generate_verifier_config(&proof)?;
+        let project = VerifierProject::generate(&conf, &cd, &vd, &proof)?;
+        assert!(project.gates_lib.contains("library PoseidonGateLib {"));
+        assert!(project.gates_lib.contains("library PoseidonMdsGateLib {"));
+        project.write("./contract")?;
+
+        Ok(())
+    }
 
-        let mut proof_file = File::create("./contract/test/data/proof.json")?;
-        proof_file.write_all(proof_json.as_bytes())?;
+    #[test]
+    fn test_recursive_verifier_without_public_inputs() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let standard_config = CircuitConfig::standard_recursion_config();
 
-        let mut conf_file = File::create("./contract/test/data/conf.json")?;
-        conf_file.write_all(serde_json::to_string(&conf)?.as_ref())?;
+        let (proof, vd, cd) = dummy_proof::<F, C, D>(&standard_config, 4_000, 0)?;
+        let WrappedProof {
+            conf,
+            contract,
+            gates_lib,
+            proof_lib,
+            proof_base64,
+            ..
+        } = wrap_for_evm(proof, vd, cd)?;
+
+        let project = VerifierProject {
+            conf,
+            contract,
+            gates_lib,
+            proof_lib,
+            proof_base64,
+        };
+        project.write("./contract")?;
 
         Ok(())
     }
@@ -0,0 +1,132 @@
+use anyhow::Result;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::fri::reduction_strategies::FriReductionStrategy;
+use plonky2::fri::FriConfig;
+use plonky2::plonk::circuit_data::{CircuitConfig, CommonCircuitData, VerifierOnlyCircuitData};
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::plonk::proof::ProofWithPublicInputs;
+
+use crate::config::KeccakGoldilocksConfig2;
+use crate::verifier::{
+    generate_proof_base64, generate_solidity_verifier, generate_verifier_config, recursive_proof,
+    VerifierConfig,
+};
+
+/// A high-rate recursive proof, designed to be verifiable with fewer routed wires.
+pub fn high_rate_config() -> CircuitConfig {
+    let standard_config = CircuitConfig::standard_recursion_config();
+    CircuitConfig {
+        fri_config: FriConfig {
+            rate_bits: 7,
+            proof_of_work_bits: 16,
+            num_query_rounds: 12,
+            ..standard_config.fri_config.clone()
+        },
+        ..standard_config
+    }
+}
+
+/// A final proof, optimized for size, to be verified on-chain.
+pub fn final_config() -> CircuitConfig {
+    CircuitConfig {
+        num_routed_wires: 37,
+        fri_config: FriConfig {
+            rate_bits: 8,
+            cap_height: 0,
+            proof_of_work_bits: 20,
+            reduction_strategy: FriReductionStrategy::MinSize(None),
+            num_query_rounds: 10,
+        },
+        ..high_rate_config()
+    }
+}
+
+/// The final proof verified on-chain, along with its verifier contracts and encoded bytes.
+pub struct WrappedProof {
+    pub proof: ProofWithPublicInputs<GoldilocksField, KeccakGoldilocksConfig2, 2>,
+    pub verifier_only: VerifierOnlyCircuitData<KeccakGoldilocksConfig2, 2>,
+    pub common: CommonCircuitData<GoldilocksField, KeccakGoldilocksConfig2, 2>,
+    pub conf: VerifierConfig,
+
+    /// Verifier.sol
+    pub contract: String,
+    /// GatesLib.sol
+    pub gates_lib: String,
+    /// ProofLib.sol
+    pub proof_lib: String,
+
+    /// The proof bytes passed to `execute_verify`, and their base64 encoding. See
+    /// `calldata::encode_calldata` for the ABI encoded call.
+    pub proof_bytes: Vec<u8>,
+    pub proof_base64: String,
+}
+
+/// Wraps a proof into a `KeccakGoldilocksConfig2` proof verifiable on-chain, going through
+/// `high_rate_config` and `final_config` recursive proofs.
+pub fn wrap_for_evm<InnerC: GenericConfig<2, F = GoldilocksField>>(
+    inner_proof: ProofWithPublicInputs<GoldilocksField, InnerC, 2>,
+    inner_vd: VerifierOnlyCircuitData<InnerC, 2>,
+    inner_cd: CommonCircuitData<GoldilocksField, InnerC, 2>,
+) -> Result<WrappedProof>
+where
+    InnerC::Hasher: AlgebraicHasher<GoldilocksField>,
+{
+    wrap_for_evm_with_configs(
+        inner_proof,
+        inner_vd,
+        inner_cd,
+        &high_rate_config(),
+        &final_config(),
+    )
+}
+
+/// Same as `wrap_for_evm`, with custom configs for the high-rate and final recursive proofs.
+pub fn wrap_for_evm_with_configs<InnerC: GenericConfig<2, F = GoldilocksField>>(
+    inner_proof: ProofWithPublicInputs<GoldilocksField, InnerC, 2>,
+    inner_vd: VerifierOnlyCircuitData<InnerC, 2>,
+    inner_cd: CommonCircuitData<GoldilocksField, InnerC, 2>,
+    high_rate_config: &CircuitConfig,
+    final_config: &CircuitConfig,
+) -> Result<WrappedProof>
+where
+    InnerC::Hasher: AlgebraicHasher<GoldilocksField>,
+{
+    type C = PoseidonGoldilocksConfig;
+    type KC2 = KeccakGoldilocksConfig2;
+
+    let (proof, vd, cd) = recursive_proof::<GoldilocksField, C, InnerC, 2>(
+        inner_proof,
+        inner_vd,
+        inner_cd,
+        high_rate_config,
+        None,
+        false,
+        false,
+    )?;
+    let (proof, vd, cd) = recursive_proof::<GoldilocksField, KC2, C, 2>(
+        proof,
+        vd,
+        cd,
+        final_config,
+        None,
+        false,
+        false,
+    )?;
+
+    let conf = generate_verifier_config(&proof)?;
+    let (contract, gates_lib, proof_lib) = generate_solidity_verifier(&conf, &cd, &vd)?;
+    let proof_base64 = generate_proof_base64(&proof, &conf)?;
+    let proof_bytes = proof.to_bytes()?;
+
+    Ok(WrappedProof {
+        proof,
+        verifier_only: vd,
+        common: cd,
+        conf,
+        contract,
+        gates_lib,
+        proof_lib,
+        proof_bytes,
+        proof_base64,
+    })
+}